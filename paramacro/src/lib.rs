@@ -1,8 +1,8 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::{quote, ToTokens};
-use syn::{parse_macro_input, Expr, Fields, Item, Lit};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Expr, Fields, Lit};
 
 // TODO: clean up this mess.
 
@@ -16,9 +16,9 @@ pub fn serialized_struct(_metadata: TokenStream, input: TokenStream)
         panic!("This must be given a name!")
     };
 
-    let mut field_types = vec![];
-    let mut field_idents = vec![];
-    let mut field_names = vec![];
+    let mut field_sizes = vec![];
+
+    let mut new_fields = vec![];
 
     for field in &mut input.fields {
         let our_custom = &field.attrs[0];
@@ -35,20 +35,51 @@ pub fn serialized_struct(_metadata: TokenStream, input: TokenStream)
             _ => None
         }.unwrap();
         field.attrs.clear();
-        field_types.push(field.ty.clone());
-        field_idents.push(field.ident.clone());
-        field_names.push(our_custom_name.clone());
+        let field_type = &field.ty;
+        let field_ident = field.ident.as_ref().unwrap();
+        let field_vis = &field.vis;
         let field_tokens = field.to_token_stream();
-        let new_field_token_stream = quote! {
-            #[br(parse_with = crate::structure::read_struct_field, args(#our_custom_name))]
-            #[bw(write_with = crate::structure::write_struct_field, args(#our_custom_name))]
-            #field_tokens
-        };
-        let buffer = ::syn::parse::Parser::parse2(
-            syn::Field::parse_named,
-            new_field_token_stream,
-        ).unwrap();
-        *field = buffer;
+        // The value and its tag are read together, and then split into two fields
+        let read_ident = format_ident!("__{}_with_tag", field_ident);
+        let tag_ident = format_ident!("{}_tag", field_ident);
+        // Static arrays are the same property repeated with a different array index, and have a tag for each element
+        let tag_type = quote! { <#field_type as crate::structure::StructField>::Tags };
+        field_sizes.push(quote! {
+            crate::structure::StructField::field_size_in_bytes(&self.#field_ident, #our_custom_name, &self.#tag_ident)
+        });
+        let new_field_token_streams = [
+            quote! {
+                #[br(temp, parse_with = crate::structure::read_struct_field, args(#our_custom_name))]
+                #[bw(ignore)]
+                #read_ident: (#field_type, #tag_type)
+            },
+            quote! {
+                #[br(calc = #read_ident.0)]
+                #[bw(write_with = crate::structure::write_struct_field, args(#our_custom_name, #tag_ident))]
+                #field_tokens
+            },
+            quote! {
+                /// The tag of the field if it's a struct (one for each element of a static array), kept so it's written back unchanged.
+                #[doc(hidden)]
+                #[br(calc = #read_ident.1)]
+                #[bw(ignore)]
+                #field_vis #tag_ident: #tag_type
+            },
+        ];
+        for new_field_token_stream in new_field_token_streams {
+            let buffer = ::syn::parse::Parser::parse2(
+                syn::Field::parse_named,
+                new_field_token_stream,
+            ).unwrap();
+            new_fields.push(buffer);
+        }
+    }
+
+    match &mut input.fields {
+        Fields::Named(_0) => {
+            _0.named = new_fields.into_iter().collect()
+        }
+        _ => {}
     }
 
     // Add "None" field
@@ -68,6 +99,33 @@ pub fn serialized_struct(_metadata: TokenStream, input: TokenStream)
         _ => {}
     }
 
+    // Top-level objects also have their size in front, and an empty type name after "None" like GenericTaggedObject
+    if struct_name.value().is_empty() {
+        let size_field_stream = quote! {
+            #[br(temp)]
+            #[bw(calc = crate::property::PropertyBase::size_in_bytes(self) + 4)]
+            size_in_bytes: u32
+        };
+        let padding_field_stream = quote! {
+            #[br(temp)]
+            #[bw(calc = 0)]
+            none_type_name: u32
+        };
+        match &mut input.fields {
+            Fields::Named(_0) => {
+                _0.named.insert(0, ::syn::parse::Parser::parse2(
+                    syn::Field::parse_named,
+                    size_field_stream,
+                ).unwrap());
+                _0.named.push(::syn::parse::Parser::parse2(
+                    syn::Field::parse_named,
+                    padding_field_stream,
+                ).unwrap());
+            }
+            _ => {}
+        }
+    }
+
     let id = &input.ident;
 
     let output = quote! {
@@ -85,7 +143,7 @@ pub fn serialized_struct(_metadata: TokenStream, input: TokenStream)
             }
 
             fn size_in_bytes(&self) -> u32 {
-                #( #field_types::size_in_bytes(&self.#field_idents) )+* + #( (crate::structure::calc_struct_field_prelude_byte_size(stringify!(#field_types), #field_names, #field_types::struct_name()) ) )+* + 9 // for "none" field
+                #( #field_sizes )+* + 9 // for "none" field
             }
         }
     };
@@ -1,13 +1,49 @@
 use binrw::BinRead;
-use ireko::CompressedSaveFile;
 use ireko::save_object::generic::GenericTaggedObject;
+use ireko::{CompressedSaveFile, decompress_to_bytes};
 use std::env;
 use std::io::Cursor;
 
+const USAGE: &str = "Usage: ireko <file.sav> [--dump-raw <path>]
+       ireko to-json <file.sav>
+       ireko from-json <file.json> -o <file.sav>";
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("to-json") => to_json(&args[1..]),
+        Some("from-json") => from_json(&args[1..]),
+        _ => print_debug(&args),
+    }
+}
+
+fn print_debug(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut path = None;
+    let mut dump_raw_path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dump-raw" => {
+                dump_raw_path = Some(args.next().ok_or("--dump-raw needs a path")?);
+            }
+            _ => path = Some(arg),
+        }
+    }
+
+    let Some(path) = path else {
+        eprintln!("{USAGE}");
+        std::process::exit(1);
+    };
+
+    let mut data = Cursor::new(std::fs::read(path)?);
 
-    let mut data = Cursor::new(std::fs::read(&args[1])?);
+    // useful for looking at the decompressed data in a hex editor
+    if let Some(dump_raw_path) = dump_raw_path {
+        std::fs::write(dump_raw_path, decompress_to_bytes(&mut data)?)?;
+        data.set_position(0);
+    }
 
     let compressed = CompressedSaveFile::<GenericTaggedObject>::read_le(&mut data)?;
     println!("{:#?}", compressed);
@@ -24,3 +60,63 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Prints the save as JSON, which can be turned back into the same save with [from_json].
+#[cfg(feature = "cli")]
+fn to_json(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let [path] = args else {
+        eprintln!("{USAGE}");
+        std::process::exit(1);
+    };
+
+    let mut data = Cursor::new(std::fs::read(path)?);
+    let compressed = CompressedSaveFile::<GenericTaggedObject>::read_le(&mut data)?;
+
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &compressed)?;
+    std::io::Write::write_all(&mut stdout, b"\n")?;
+
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn from_json(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    use binrw::BinWrite;
+
+    let mut path = None;
+    let mut output_path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => {
+                output_path = Some(args.next().ok_or("-o needs a path")?);
+            }
+            _ => path = Some(arg),
+        }
+    }
+
+    let (Some(path), Some(output_path)) = (path, output_path) else {
+        eprintln!("{USAGE}");
+        std::process::exit(1);
+    };
+
+    let compressed: CompressedSaveFile<GenericTaggedObject> =
+        serde_json::from_slice(&std::fs::read(path)?)?;
+
+    let mut data = Cursor::new(Vec::new());
+    compressed.write_le(&mut data)?;
+    std::fs::write(output_path, data.into_inner())?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "cli"))]
+fn to_json(_: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    Err("ireko has to be built with the cli feature to convert to JSON".into())
+}
+
+#[cfg(not(feature = "cli"))]
+fn from_json(_: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    Err("ireko has to be built with the cli feature to convert from JSON".into())
+}
@@ -1,5 +1,66 @@
+use std::cell::Cell;
+use std::io::{Read, Seek, SeekFrom, Write};
+
 use binrw::BinResult;
-use binrw::{BinRead, BinWrite};
+use binrw::{BinRead, BinWrite, Endian};
+
+use crate::Error;
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Reads `T` like [BinRead::read_le], but fails with [Error::NonZeroPadding] if any bytes that we keep without understanding them aren't zero.
+///
+/// These bytes are always written back unchanged, so this is only needed to find saves that have something in them.
+pub fn read_strict<T, R>(reader: &mut R) -> BinResult<T>
+where
+    T: for<'a> BinRead<Args<'a> = ()>,
+    R: Read + Seek,
+{
+    // Put the old value back even if reading panics
+    struct Reset(bool);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            STRICT.set(self.0);
+        }
+    }
+
+    let _reset = Reset(STRICT.replace(true));
+    T::read_le(reader)
+}
+
+/// Errors out if `is_zero` isn't true while reading with [read_strict].
+pub(crate) fn check_padding(pos: u64, is_zero: bool) -> BinResult<()> {
+    if !is_zero && STRICT.get() {
+        return Err(Error::NonZeroPadding { pos }.into());
+    }
+    Ok(())
+}
+
+/// Writes `value`, and then goes back to fill in its size in the `u32` that was written right before it.
+#[binrw::writer(writer, endian)]
+pub(crate) fn write_with_size_before<T>(value: &T) -> BinResult<()>
+where
+    T: for<'a> BinWrite<Args<'a> = ()>,
+{
+    let start = writer.stream_position()?;
+    value.write_options(writer, endian, ())?;
+    fill_in_size_before(writer, endian, start)
+}
+
+/// Goes back to put the size of everything written since `start` in the `u32` right before it.
+pub(crate) fn fill_in_size_before<W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    start: u64,
+) -> BinResult<()> {
+    let end = writer.stream_position()?;
+    writer.seek(SeekFrom::Start(start - 4))?;
+    ((end - start) as u32).write_options(writer, endian, ())?;
+    writer.seek(SeekFrom::Start(end))?;
+    Ok(())
+}
 
 pub(crate) fn read_bool_from<T: From<u8> + PartialEq>(x: T) -> bool {
     x == T::from(1u8)
@@ -9,47 +70,90 @@ pub(crate) fn write_bool_as<T: From<u8>>(x: &bool) -> T {
     if *x { T::from(1u8) } else { T::from(0u8) }
 }
 
-#[binrw::parser(reader, endian)]
+/// Gives the current position, for fields that need to know where something started.
+#[binrw::parser(reader)]
+pub(crate) fn current_position() -> BinResult<u64> {
+    Ok(reader.stream_position()?)
+}
+
+/// Reads an FString.
+///
+/// A positive length means it's stored as a single byte per character, while a negative length means UTF-16LE. Either way the length includes the null terminator.
+#[binrw::parser(reader)]
 pub(crate) fn read_string_with_length() -> BinResult<String> {
-    let length = u32::read_le(reader)? as usize;
+    let pos = reader.stream_position()?;
+    let length = i32::read_le(reader)?;
     if length == 0 {
         return Ok(String::default());
     }
+
+    // The length comes straight from the file, so make sure there's that much data left before trusting it
+    let is_utf16 = length < 0;
+    let size = length.unsigned_abs() as u64 * if is_utf16 { 2 } else { 1 };
+    let start = reader.stream_position()?;
+    let end = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(start))?;
+    if size > end.saturating_sub(start) {
+        return Err(Error::InvalidString { pos }.into());
+    }
+
+    let mut bytes = Vec::new();
+    reader.by_ref().take(size).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != size {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+
+    if is_utf16 {
+        // last character is the null terminator which Rust ignores
+        let units: Vec<u16> = bytes[..bytes.len() - 2]
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect();
+        return String::from_utf16(&units).or(Err(Error::InvalidString { pos }.into()));
+    }
     // last byte is the null terminator which Rust ignores
-    let length = length - 1;
-    let mut bytes: Vec<u8> = vec![0u8; length];
-    // TODO: there was to be way to read this all in one go?
-    for i in 0..length {
-        bytes[i] = u8::read_le(reader)?;
-    }
-    u8::read_le(reader)?; // read null terminator
-    String::from_utf8(bytes).or(Err(binrw::Error::AssertFail {
-        pos: 0,
-        message: "dummy".to_string(),
-    }))
+    bytes.pop();
+    String::from_utf8(bytes).or(Err(Error::InvalidString { pos }.into()))
 }
 
-#[binrw::writer(writer, endian)]
+/// Writes an FString, using UTF-16LE if it doesn't fit in ASCII like the engine does.
+// binrw hands write_with the field by reference, so this has to take a &String
+#[allow(clippy::ptr_arg)]
+#[binrw::writer(writer)]
 pub(crate) fn write_string_with_length(string: &String) -> BinResult<()> {
     if string.is_empty() {
-        let length = 0u32;
+        let length = 0i32;
         length.write_le(writer)?;
         return Ok(());
     }
+    if !string.is_ascii() {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        // + 1 for the null terminator, and negative to mark it as UTF-16
+        let length = -(units.len() as i32 + 1);
+        length.write_le(writer)?;
+        units.write_le(writer)?;
+        let null_terminator = 0u16;
+        null_terminator.write_le(writer)?;
+        return Ok(());
+    }
     // + 1 for the null terminator
-    let length = string.len() as u32 + 1;
+    let length = string.len() as i32 + 1;
     length.write_le(writer)?;
-    for char in string.chars() {
-        let byte = char as u8;
-        byte.write_le(writer)?;
-    }
+    writer.write_all(string.as_bytes())?;
     let null_terminator = 0u8;
     null_terminator.write_le(writer)?;
     Ok(())
 }
 
+/// Size of an FString as written by [write_string_with_length].
 pub(crate) fn size_of_string_with_length(string: &str) -> u32 {
-    4 + string.len() as u32 + 1
+    if string.is_empty() {
+        4
+    } else if !string.is_ascii() {
+        4 + (string.encode_utf16().count() as u32 + 1) * 2
+    } else {
+        4 + string.len() as u32 + 1
+    }
 }
 
 #[cfg(test)]
@@ -82,4 +186,80 @@ mod tests {
         let decoded = TestStruct::read_le(&mut cursor).unwrap();
         assert_eq!(decoded.value, "redstrate");
     }
+
+    #[test]
+    fn read_invalid_string() {
+        let data = [0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0xff, 0xfe, 0x00];
+
+        let mut cursor = Cursor::new(data);
+        cursor.set_position(2);
+        let err = read_string_with_length(&mut cursor, binrw::Endian::Little, ()).unwrap_err();
+        assert_eq!(
+            err.custom_err::<Error>(),
+            Some(&Error::InvalidString { pos: 2 })
+        );
+    }
+
+    #[test]
+    fn read_string_too_long() {
+        // i32::MIN as the length, which would be 4 GiB of UTF-16
+        let data = [0x00, 0x00, 0x00, 0x80, 0x41, 0x00, 0x00, 0x00];
+
+        let mut cursor = Cursor::new(data);
+        let err = read_string_with_length(&mut cursor, binrw::Endian::Little, ()).unwrap_err();
+        assert_eq!(
+            err.custom_err::<Error>(),
+            Some(&Error::InvalidString { pos: 0 })
+        );
+
+        let data = [0x0a, 0x00, 0x00, 0x00, 0x72, 0x65, 0x64, 0x00];
+
+        let mut cursor = Cursor::new(data);
+        let err = read_string_with_length(&mut cursor, binrw::Endian::Little, ()).unwrap_err();
+        assert_eq!(
+            err.custom_err::<Error>(),
+            Some(&Error::InvalidString { pos: 0 })
+        );
+    }
+
+    #[test]
+    fn read_utf16_string() {
+        // "プレイヤー"
+        let data = [
+            0xfa, 0xff, 0xff, 0xff, 0xd7, 0x30, 0xec, 0x30, 0xa4, 0x30, 0xe4, 0x30, 0xfc, 0x30,
+            0x00, 0x00,
+        ];
+
+        let mut cursor = Cursor::new(data);
+        let decoded = read_string_with_length(&mut cursor, binrw::Endian::Little, ()).unwrap();
+        assert_eq!(decoded, "プレイヤー");
+        assert_eq!(cursor.position(), data.len() as u64);
+    }
+
+    #[test]
+    fn write_utf16_string() {
+        let expected_data = [
+            0xfa, 0xff, 0xff, 0xff, 0xd7, 0x30, 0xec, 0x30, 0xa4, 0x30, 0xe4, 0x30, 0xfc, 0x30,
+            0x00, 0x00,
+        ];
+        let string = "プレイヤー".to_string();
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            write_string_with_length(&string, &mut cursor, binrw::Endian::Little, ()).unwrap();
+        }
+
+        assert_eq!(expected_data, &buffer[..]);
+        assert_eq!(
+            size_of_string_with_length(&string),
+            expected_data.len() as u32
+        );
+    }
+
+    #[test]
+    fn string_sizes() {
+        assert_eq!(size_of_string_with_length(""), 4);
+        assert_eq!(size_of_string_with_length("redstrate"), 14);
+    }
 }
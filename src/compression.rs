@@ -0,0 +1,618 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use binrw::{BinRead, BinResult, BinWrite, binread, binrw};
+use flate2::Compression;
+use flate2::bufread::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+
+use crate::Error;
+
+/// How big blocks and chunks are in saves written by the game.
+const DEFAULT_CHUNK_SIZE: u64 = 0x20000;
+
+/// Controls how data is split up and compressed when writing a [CompressedSaveFile](crate::CompressedSaveFile).
+///
+/// The defaults match what the game writes. Note that byte-identical output also depends on the zlib implementation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompressionOptions {
+    /// Maximum amount of uncompressed data in a single block. The game starts a new block every 128 KiB.
+    pub block_size: u64,
+    /// Maximum amount of uncompressed data in a single chunk of a block, which is also written into the block header.
+    pub chunk_size: u64,
+    /// The zlib compression level, from 0 to 9.
+    pub level: u32,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        Self {
+            block_size: DEFAULT_CHUNK_SIZE,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            level: 6,
+        }
+    }
+}
+
+/// The size of a chunk before and after compression.
+#[binrw]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressedChunkInfo {
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+}
+
+/// The header of a compressed block.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/Core/Serialization/FArchive/SerializeCompressed?application_version=4.27).
+#[binrw]
+#[brw(magic = 0x9e2a83c1u64)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedBlockHeader {
+    /// Maximum uncompressed size of each chunk.
+    #[br(assert(chunk_size != 0))]
+    pub chunk_size: u64,
+    /// Combined sizes of all the chunks in this block.
+    pub summary: CompressedChunkInfo,
+    #[br(count = summary.uncompressed_size.div_ceil(chunk_size))]
+    #[br(assert(chunks.iter().map(|x| x.uncompressed_size).sum::<u64>() == summary.uncompressed_size, "chunk sizes don't add up to the block size"))]
+    pub chunks: Vec<CompressedChunkInfo>,
+}
+
+/// A block of the save data, which is made up of one or more zlib-compressed chunks.
+///
+/// When written, `data` is compressed again and the sizes are recalculated from the result.
+/// The index passed when reading is only used for error messages.
+#[binread]
+#[derive(Debug)]
+#[br(import(index: usize))]
+pub struct CompressedBlock {
+    pub header: CompressedBlockHeader,
+    #[br(parse_with = read_compressed_data, args(&header.chunks, index))]
+    pub data: Vec<u8>,
+}
+
+impl BinWrite for CompressedBlock {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let options = CompressionOptions {
+            chunk_size: self.header.chunk_size,
+            ..Default::default()
+        };
+        write_compressed_block(&self.data, writer, endian, (&options,))
+    }
+}
+
+/// Where a block is located in the file, and how it was compressed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedBlockLayout {
+    /// Offset of the block header in the file.
+    pub offset: u64,
+    pub header: CompressedBlockHeader,
+    /// The compression level of the first chunk, as far as it can be told from the start of its zlib stream.
+    ///
+    /// The zlib header only says which range the level is in, so this is exact for 0, 1, 6 and 9, and other levels come back as whichever
+    /// of 1, 5, 6 or 9 stands for their range. Where the ranges start depends on the zlib implementation.
+    pub level: u32,
+}
+
+impl CompressedBlockLayout {
+    /// Offset of the compressed chunks in the file, right after the header.
+    pub fn data_offset(&self) -> u64 {
+        // magic, chunk size, summary and one info per chunk
+        self.offset + 8 + 8 + 16 + 16 * self.header.chunks.len() as u64
+    }
+
+    /// Offset right after the last compressed chunk in the file.
+    pub fn end_offset(&self) -> u64 {
+        self.data_offset()
+            + self
+                .header
+                .chunks
+                .iter()
+                .map(|x| x.compressed_size)
+                .sum::<u64>()
+    }
+}
+
+/// The layout of the blocks in a compressed save file, read without decompressing anything.
+#[binread]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionLayout {
+    #[br(parse_with = read_block_layouts)]
+    pub blocks: Vec<CompressedBlockLayout>,
+}
+
+impl CompressionLayout {
+    /// Options that reproduce this layout when writing.
+    ///
+    /// The level is only exact if it was 0, 1, 6 or 9, see [CompressedBlockLayout::level].
+    pub fn options(&self) -> CompressionOptions {
+        let mut options = CompressionOptions::default();
+        if let Some(first) = self.blocks.first() {
+            options.chunk_size = first.header.chunk_size;
+            options.level = first.level;
+        }
+        // Only a block that was followed by another one tells us where the writer decided to split
+        if self.blocks.len() > 1 {
+            options.block_size = self.blocks[0].header.summary.uncompressed_size;
+        }
+        options
+    }
+}
+
+/// Reads the decompressed save data straight from the compressed blocks.
+///
+/// Blocks are only decompressed once they are read from, and only one is kept in memory at a time.
+pub struct DecompressingReader<R> {
+    reader: R,
+    blocks: Vec<CompressedBlockLayout>,
+    /// Where each block starts in the decompressed data.
+    block_starts: Vec<u64>,
+    len: u64,
+    position: u64,
+    current_block: Option<(usize, Vec<u8>)>,
+}
+
+impl<R: Read + Seek> DecompressingReader<R> {
+    /// Reads the layout of the blocks starting at the current position of `reader`.
+    pub fn new(mut reader: R) -> BinResult<Self> {
+        let layout = CompressionLayout::read_le(&mut reader)?;
+        Ok(Self::with_layout(reader, layout))
+    }
+
+    /// Uses an already known layout, which has to match the blocks in `reader`.
+    pub fn with_layout(reader: R, layout: CompressionLayout) -> Self {
+        let mut block_starts = Vec::with_capacity(layout.blocks.len());
+        let mut len = 0;
+        for block in &layout.blocks {
+            block_starts.push(len);
+            len += block.header.summary.uncompressed_size;
+        }
+
+        Self {
+            reader,
+            blocks: layout.blocks,
+            block_starts,
+            len,
+            position: 0,
+            current_block: None,
+        }
+    }
+
+    /// Size of the decompressed data.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether there is any decompressed data at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the underlying reader, positioned after the last block.
+    pub fn into_inner(mut self) -> std::io::Result<R> {
+        if let Some(last) = self.blocks.last() {
+            self.reader.seek(SeekFrom::Start(last.end_offset()))?;
+        }
+        Ok(self.reader)
+    }
+
+    fn load_block(&mut self, index: usize) -> BinResult<()> {
+        if matches!(&self.current_block, Some((current, _)) if *current == index) {
+            return Ok(());
+        }
+
+        let block = &self.blocks[index];
+        self.reader.seek(SeekFrom::Start(block.data_offset()))?;
+        let data = read_compressed_data(
+            &mut self.reader,
+            binrw::Endian::Little,
+            (&block.header.chunks, index),
+        )?;
+        self.current_block = Some((index, data));
+
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for DecompressingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let index = self.block_starts.partition_point(|x| *x <= self.position) - 1;
+        self.load_block(index).map_err(|err| match err {
+            binrw::Error::Io(err) => err,
+            binrw::Error::Custom { err, .. } => match err.downcast::<Error>() {
+                Ok(err) => std::io::Error::new(std::io::ErrorKind::InvalidData, *err),
+                Err(err) => std::io::Error::other(err.to_string()),
+            },
+            err => std::io::Error::other(err),
+        })?;
+
+        let Some((_, data)) = &self.current_block else {
+            unreachable!()
+        };
+        let offset = (self.position - self.block_starts[index]) as usize;
+        let available = data.len().saturating_sub(offset);
+        if available == 0 {
+            return Ok(0);
+        }
+
+        let size = available.min(buf.len());
+        buf[..size].copy_from_slice(&data[offset..offset + size]);
+        self.position += size as u64;
+
+        Ok(size)
+    }
+}
+
+impl<R: Read + Seek> Seek for DecompressingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        match new_position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            )),
+        }
+    }
+}
+
+/// Guesses the compression level from the start of a zlib stream, which is the two byte header and the first byte of deflate data.
+///
+/// The FLEVEL bits of the header only say which range the level is in, and each range is read as 1, 5, 6 or 9. Level 0 shares its range
+/// with level 1, but only level 0 starts with a stored block instead of a compressed one.
+fn compression_level(zlib_start: [u8; 3]) -> u32 {
+    match zlib_start[1] >> 6 {
+        // The block type is in bits 1 and 2 of the first deflate byte
+        0 if (zlib_start[2] >> 1) & 0b11 == 0 => 0,
+        0 => 1,
+        1 => 5,
+        2 => 6,
+        _ => 9,
+    }
+}
+
+#[binrw::parser(reader, endian)]
+fn read_block_layouts() -> BinResult<Vec<CompressedBlockLayout>> {
+    let start = reader.stream_position()?;
+    let end = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(start))?;
+
+    let mut blocks = Vec::new();
+    let mut offset = start;
+    while offset < end {
+        let header = CompressedBlockHeader::read_options(reader, endian, ())?;
+
+        let mut level = CompressionOptions::default().level;
+        if header
+            .chunks
+            .first()
+            .is_some_and(|x| x.compressed_size >= 3)
+        {
+            let mut zlib_start = [0u8; 3];
+            reader.read_exact(&mut zlib_start)?;
+            level = compression_level(zlib_start);
+            reader.seek(SeekFrom::Current(-3))?;
+        }
+
+        let compressed_size: u64 = header.chunks.iter().map(|x| x.compressed_size).sum();
+        reader.seek(SeekFrom::Current(compressed_size as i64))?;
+
+        blocks.push(CompressedBlockLayout {
+            offset,
+            header,
+            level,
+        });
+        offset = reader.stream_position()?;
+    }
+
+    Ok(blocks)
+}
+
+#[binrw::parser(reader)]
+fn read_compressed_data(chunks: &[CompressedChunkInfo], block: usize) -> BinResult<Vec<u8>> {
+    let mut uncompressed = Vec::new();
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        let offset = reader.stream_position()?;
+
+        // A truncated file is caught below, since it can't decompress to the full size
+        let mut compressed_data = Vec::new();
+        (&mut *reader)
+            .take(chunk.compressed_size)
+            .read_to_end(&mut compressed_data)?;
+
+        let mut decompressed = Vec::new();
+        let mut d = ZlibDecoder::new(&*compressed_data);
+        let result = d.read_to_end(&mut decompressed);
+
+        if result.is_err() || decompressed.len() as u64 != chunk.uncompressed_size {
+            return Err(Error::CorruptBlock {
+                block,
+                chunk: index,
+                offset,
+                expected_size: chunk.uncompressed_size,
+                actual_size: decompressed.len() as u64,
+            }
+            .into());
+        }
+
+        uncompressed.extend_from_slice(&decompressed);
+    }
+
+    Ok(uncompressed)
+}
+
+/// Decompresses all blocks starting at the current position of `reader`, giving the raw save data.
+///
+/// This is the same data a [CompressedSaveFile](crate::CompressedSaveFile) is parsed from, which is useful for debugging.
+pub fn decompress_to_bytes<R: Read + Seek>(reader: &mut R) -> BinResult<Vec<u8>> {
+    let mut decompressed = DecompressingReader::new(reader)?;
+
+    let mut data = Vec::with_capacity(decompressed.len() as usize);
+    decompressed
+        .read_to_end(&mut data)
+        .map_err(|err| crate::unwrap_block_error(binrw::Error::Io(err)))?;
+
+    Ok(data)
+}
+
+/// Compresses raw save data into blocks, the reverse of [decompress_to_bytes].
+pub fn compress_bytes(data: &[u8], options: &CompressionOptions) -> BinResult<Vec<u8>> {
+    let mut compressed = std::io::Cursor::new(Vec::new());
+    write_compressed_blocks(data, &mut compressed, binrw::Endian::Little, (options,))?;
+
+    Ok(compressed.into_inner())
+}
+
+/// Splits `data` into blocks and writes them.
+#[binrw::writer(writer, endian)]
+pub(crate) fn write_compressed_blocks(data: &[u8], options: &CompressionOptions) -> BinResult<()> {
+    let message = if options.block_size == 0 {
+        Some("block size can't be zero")
+    } else if options.chunk_size == 0 {
+        Some("chunk size can't be zero")
+    } else if options.level > 9 {
+        Some("level has to be from 0 to 9")
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        return Err(Error::InvalidCompressionOptions {
+            pos: writer.stream_position()?,
+            message: message.to_string(),
+        }
+        .into());
+    }
+
+    for block in data.chunks(options.block_size as usize) {
+        write_compressed_block(block, writer, endian, (options,))?;
+    }
+
+    Ok(())
+}
+
+#[binrw::writer(writer, endian)]
+fn write_compressed_block(data: &[u8], options: &CompressionOptions) -> BinResult<()> {
+    let mut chunks = Vec::new();
+    let mut compressed_data = Vec::new();
+    for chunk in data.chunks(options.chunk_size as usize) {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::new(options.level));
+        encoder.write_all(chunk)?;
+        let compressed = encoder.finish()?;
+
+        chunks.push(CompressedChunkInfo {
+            compressed_size: compressed.len() as u64,
+            uncompressed_size: chunk.len() as u64,
+        });
+        compressed_data.extend_from_slice(&compressed);
+    }
+
+    let header = CompressedBlockHeader {
+        chunk_size: options.chunk_size,
+        summary: CompressedChunkInfo {
+            compressed_size: compressed_data.len() as u64,
+            uncompressed_size: data.len() as u64,
+        },
+        chunks,
+    };
+    header.write_options(writer, endian, ())?;
+    compressed_data.write_options(writer, endian, ())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_header() {
+        // A full block with a single chunk
+        let data = [
+            0xc1, 0x83, 0x2a, 0x9e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x4d, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x4d, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = CompressedBlockHeader::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.chunk_size, 0x20000);
+        assert_eq!(decoded.summary.compressed_size, 0x3f4d);
+        assert_eq!(decoded.summary.uncompressed_size, 0x20000);
+        assert_eq!(decoded.chunks, vec![decoded.summary]);
+    }
+
+    #[test]
+    fn write_chunked() {
+        let data: Vec<u8> = (0..100u8).cycle().take(0x500).collect();
+        let options = CompressionOptions {
+            block_size: 0x400,
+            chunk_size: 0x100,
+            level: 9,
+        };
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            write_compressed_blocks(&data, &mut cursor, binrw::Endian::Little, (&options,))
+                .unwrap();
+        }
+
+        let layout = CompressionLayout::read_le(&mut Cursor::new(&buffer)).unwrap();
+        assert_eq!(layout.blocks.len(), 2);
+        assert_eq!(layout.blocks[0].header.chunks.len(), 4);
+        assert_eq!(layout.blocks[1].header.chunks.len(), 1);
+        assert_eq!(layout.options(), options);
+
+        let mut cursor = Cursor::new(&buffer);
+        let first = CompressedBlock::read_le_args(&mut cursor, (0,)).unwrap();
+        let second = CompressedBlock::read_le_args(&mut cursor, (1,)).unwrap();
+        assert_eq!([first.data, second.data].concat(), data);
+    }
+
+    #[test]
+    fn detect_level() {
+        let data: Vec<u8> = (0..100u8).cycle().take(0x500).collect();
+        for level in [0, 1, 6, 9] {
+            let options = CompressionOptions {
+                level,
+                ..Default::default()
+            };
+            let compressed = compress_bytes(&data, &options).unwrap();
+
+            let layout = CompressionLayout::read_le(&mut Cursor::new(&compressed)).unwrap();
+            assert_eq!(layout.options(), options);
+            assert_eq!(
+                compress_bytes(&data, &layout.options()).unwrap(),
+                compressed
+            );
+        }
+
+        // Other levels only come back as the one that stands for their range
+        let options = CompressionOptions {
+            level: 2,
+            ..Default::default()
+        };
+        let compressed = compress_bytes(&data, &options).unwrap();
+        let layout = CompressionLayout::read_le(&mut Cursor::new(&compressed)).unwrap();
+        assert_eq!(layout.options().level, 5);
+    }
+
+    #[test]
+    fn write_invalid_options() {
+        for options in [
+            CompressionOptions {
+                block_size: 0,
+                ..Default::default()
+            },
+            CompressionOptions {
+                chunk_size: 0,
+                ..Default::default()
+            },
+            CompressionOptions {
+                level: 10,
+                ..Default::default()
+            },
+        ] {
+            let err = compress_bytes(&[0; 16], &options).unwrap_err();
+            assert!(matches!(
+                err.custom_err::<Error>(),
+                Some(Error::InvalidCompressionOptions { pos: 0, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn read_streaming() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(0x500).collect();
+        let options = CompressionOptions {
+            block_size: 0x200,
+            chunk_size: 0x100,
+            ..Default::default()
+        };
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            write_compressed_blocks(&data, &mut cursor, binrw::Endian::Little, (&options,))
+                .unwrap();
+        }
+
+        let mut reader = DecompressingReader::new(Cursor::new(&buffer)).unwrap();
+        assert_eq!(reader.len(), data.len() as u64);
+
+        let mut decompressed = Vec::new();
+        reader.read_to_end(&mut decompressed).unwrap();
+        assert_eq!(decompressed, data);
+
+        // Jump back into the first block, and read across into the second one
+        reader.seek(SeekFrom::Start(0x1fe)).unwrap();
+        let mut value = [0u8; 4];
+        reader.read_exact(&mut value).unwrap();
+        assert_eq!(value, [0xfe, 0xff, 0x00, 0x01]);
+
+        let inner = reader.into_inner().unwrap();
+        assert_eq!(inner.position(), buffer.len() as u64);
+    }
+
+    #[test]
+    fn read_truncated() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(0x300).collect();
+        let options = CompressionOptions {
+            block_size: 0x100,
+            chunk_size: 0x100,
+            level: 0,
+        };
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            write_compressed_blocks(&data, &mut cursor, binrw::Endian::Little, (&options,))
+                .unwrap();
+        }
+
+        let layout = CompressionLayout::read_le(&mut Cursor::new(&buffer)).unwrap();
+        let last = layout.blocks.last().unwrap().clone();
+        buffer.truncate(buffer.len() - 0x10);
+
+        let mut reader = DecompressingReader::with_layout(Cursor::new(&buffer), layout);
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        let err = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+        let Error::CorruptBlock {
+            block,
+            chunk,
+            offset,
+            expected_size,
+            actual_size,
+        } = err
+        else {
+            panic!("Expected a corrupt block, got {err}")
+        };
+        assert_eq!(*block, 2);
+        assert_eq!(*chunk, 0);
+        assert_eq!(*offset, last.data_offset());
+        assert_eq!(*expected_size, 0x100);
+        assert!(*actual_size < 0x100);
+    }
+}
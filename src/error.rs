@@ -0,0 +1,240 @@
+use std::fmt;
+
+use binrw::BinResult;
+
+/// Errors specific to reading and writing saves.
+///
+/// These are returned wrapped in [binrw::Error::Custom], use [binrw::Error::custom_err] to get at them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A compressed chunk is cut off or damaged, so it didn't decompress to the size its block header says.
+    CorruptBlock {
+        /// Index of the block in the file.
+        block: usize,
+        /// Index of the chunk in the block.
+        chunk: usize,
+        /// Offset of the chunk's compressed data in the file.
+        offset: u64,
+        expected_size: u64,
+        actual_size: u64,
+    },
+    /// The [CompressionOptions](crate::CompressionOptions) given for writing can't be used, e.g. because a size is zero.
+    InvalidCompressionOptions { pos: u64, message: String },
+    /// A property in a struct doesn't have the name it should have.
+    NameMismatch {
+        pos: u64,
+        expected: String,
+        found: String,
+    },
+    /// A property or struct doesn't have the type it should have.
+    TypeMismatch {
+        pos: u64,
+        expected: String,
+        found: String,
+    },
+    /// A string says it's longer than the data that's left, or isn't valid UTF-8 (or UTF-16 if its length is negative).
+    InvalidString { pos: u64 },
+    /// A map or set has keys or values of a type that we don't know how to read.
+    UnknownPropertyType { pos: u64, type_name: String },
+    /// A struct has a name that we don't know how to read.
+    UnknownStruct { pos: u64, struct_name: String },
+    /// A static array is missing an element, or has them out of order.
+    ArrayIndexMismatch { pos: u64, expected: u32, found: u32 },
+    /// Bytes that are usually zero and that we don't understand aren't, this is only checked by [read_strict](crate::read_strict).
+    NonZeroPadding { pos: u64 },
+    /// Reading a property used up a different amount of bytes than its size says.
+    SizeMismatch {
+        pos: u64,
+        expected_size: u64,
+        actual_size: u64,
+    },
+    /// Reading a property failed, `path` says which one (e.g. `CurrentBuildData.Assemble.ColoringData`) and `source` why.
+    InProperty { path: String, source: Box<Error> },
+    /// binrw couldn't read something, e.g. because the data ended early.
+    Read { pos: u64, message: String },
+}
+
+impl Error {
+    /// Position in the stream the error happened at.
+    ///
+    /// For [Error::CorruptBlock] this is an offset in the compressed file, otherwise it's in the decompressed data.
+    pub fn pos(&self) -> u64 {
+        match self {
+            Error::CorruptBlock { offset, .. } => *offset,
+            Error::InvalidCompressionOptions { pos, .. }
+            | Error::NameMismatch { pos, .. }
+            | Error::TypeMismatch { pos, .. }
+            | Error::InvalidString { pos }
+            | Error::NonZeroPadding { pos }
+            | Error::UnknownPropertyType { pos, .. }
+            | Error::UnknownStruct { pos, .. }
+            | Error::ArrayIndexMismatch { pos, .. }
+            | Error::SizeMismatch { pos, .. }
+            | Error::Read { pos, .. } => *pos,
+            Error::InProperty { source, .. } => source.pos(),
+        }
+    }
+
+    /// Path to the property the error happened in, if it happened in one.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Error::InProperty { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The error without any property path around it.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::InProperty { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    /// Turns any binrw error into one of ours, using `pos` if binrw doesn't know where it happened.
+    fn from_binrw(err: binrw::Error, pos: u64) -> Error {
+        match err {
+            binrw::Error::Custom { pos, err } => match err.downcast::<Error>() {
+                Ok(err) => *err,
+                Err(err) => Error::Read {
+                    pos,
+                    message: err.to_string(),
+                },
+            },
+            binrw::Error::Backtrace(backtrace) => Error::from_binrw(*backtrace.error, pos),
+            binrw::Error::Io(err) if err.get_ref().is_some_and(|x| x.is::<Error>()) => {
+                *err.into_inner().unwrap().downcast::<Error>().unwrap()
+            }
+            // Variants that didn't match fail their pre_assert or magic, so the error we care about is the other one
+            binrw::Error::EnumErrors {
+                pos,
+                variant_errors,
+            } => match variant_errors.into_iter().find(|(_, err)| {
+                !matches!(
+                    err.root_cause(),
+                    binrw::Error::AssertFail { .. } | binrw::Error::BadMagic { .. }
+                )
+            }) {
+                Some((_, err)) => Error::from_binrw(err, pos),
+                None => Error::Read {
+                    pos,
+                    message: "no variants matched".to_string(),
+                },
+            },
+            binrw::Error::BadMagic { pos, found } => Error::Read {
+                pos,
+                message: format!("bad magic {found:?}"),
+            },
+            binrw::Error::AssertFail { pos, message } => Error::Read { pos, message },
+            binrw::Error::NoVariantMatch { pos } => Error::Read {
+                pos,
+                message: "no variants matched".to_string(),
+            },
+            err => Error::Read {
+                pos,
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Puts `name` in front of the property path of the error in `result`.
+///
+/// `pos` is where the property starts, and is only used when the error doesn't already have a position.
+pub(crate) fn in_property<T>(result: BinResult<T>, name: &str, pos: u64) -> BinResult<T> {
+    result.map_err(|err| {
+        match Error::from_binrw(err, pos) {
+            // This is about the file, not the property we happened to be reading
+            err @ Error::CorruptBlock { .. } => err,
+            Error::InProperty { path, source } => {
+                let separator = if path.starts_with('[') { "" } else { "." };
+                Error::InProperty {
+                    path: format!("{name}{separator}{path}"),
+                    source,
+                }
+            }
+            err => Error::InProperty {
+                path: name.to_string(),
+                source: Box::new(err),
+            },
+        }
+        .into()
+    })
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CorruptBlock {
+                block,
+                chunk,
+                offset,
+                expected_size,
+                actual_size,
+            } => write!(
+                f,
+                "chunk {chunk} of block {block} at {offset:#x} decompressed to {actual_size} bytes, but should be {expected_size} bytes"
+            ),
+            Error::InvalidCompressionOptions { pos, message } => {
+                write!(f, "invalid compression options at {pos:#x}: {message}")
+            }
+            Error::NameMismatch {
+                pos,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected property {expected} but found {found} at {pos:#x}"
+            ),
+            Error::TypeMismatch {
+                pos,
+                expected,
+                found,
+            } => write!(f, "expected type {expected} but found {found} at {pos:#x}"),
+            Error::InvalidString { pos } => write!(f, "invalid string at {pos:#x}"),
+            Error::NonZeroPadding { pos } => write!(f, "unexpected nonzero bytes at {pos:#x}"),
+            Error::UnknownPropertyType { pos, type_name } => {
+                write!(f, "unknown property type {type_name} at {pos:#x}")
+            }
+            Error::UnknownStruct { pos, struct_name } => {
+                write!(f, "unknown struct {struct_name} at {pos:#x}")
+            }
+            Error::ArrayIndexMismatch {
+                pos,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected array index {expected} but found {found} at {pos:#x}"
+            ),
+            Error::SizeMismatch {
+                pos,
+                expected_size,
+                actual_size,
+            } => write!(
+                f,
+                "property at {pos:#x} used {actual_size} bytes, but its size is {expected_size} bytes"
+            ),
+            Error::InProperty { path, source } => write!(f, "{path}: {source}"),
+            Error::Read { pos, message } => write!(f, "{message} at {pos:#x}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InProperty { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for binrw::Error {
+    fn from(err: Error) -> Self {
+        binrw::Error::Custom {
+            pos: err.pos(),
+            err: Box::new(err),
+        }
+    }
+}
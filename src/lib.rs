@@ -1,4 +1,5 @@
 mod common;
+pub use self::common::read_strict;
 
 /// Properties
 pub mod property;
@@ -9,79 +10,96 @@ pub mod save_object;
 /// Various structures
 pub mod structure;
 
-use binrw::helpers::until_eof;
-use std::fs::write;
-use std::io::{Cursor, Read};
+mod error;
+pub use self::error::Error;
+
+mod compression;
+pub use self::compression::{
+    CompressedBlock, CompressedBlockHeader, CompressedBlockLayout, CompressedChunkInfo,
+    CompressionLayout, CompressionOptions, DecompressingReader, compress_bytes,
+    decompress_to_bytes,
+};
+
+use std::io::Cursor;
 
 use binrw::{BinRead, BinResult, BinWrite, binrw};
-use flate2::bufread::ZlibDecoder;
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TaggedSerialization<T>
 where
     for<'a> T: BinRead<Args<'a> = ()> + 'a,
     for<'a> T: BinWrite<Args<'a> = ()> + 'a,
 {
+    /// Size of `objs`, this is calculated again when writing.
     pub size_in_bytes: u32,
+    #[bw(write_with = common::write_with_size_before)]
     pub objs: T,
 }
 
-#[binrw::parser(reader, endian)]
-fn read_compressed_data(compressed_size: u64, uncompressed_size: u64) -> BinResult<Vec<u8>> {
-    let mut compressed_data = vec![0; compressed_size as usize];
-    reader.read_exact(&mut compressed_data)?;
-
-    let mut uncompressed = vec![0; uncompressed_size as usize];
+#[binrw::parser(reader)]
+fn read_tagged_data<T>(layout: &CompressionLayout) -> BinResult<TaggedSerialization<T>>
+where
+    for<'a> T: BinRead<Args<'a> = ()> + 'a,
+    for<'a> T: BinWrite<Args<'a> = ()> + 'a,
+{
+    let mut decompressed = DecompressingReader::with_layout(&mut *reader, layout.clone());
+    let value = TaggedSerialization::<T>::read_le(&mut decompressed).map_err(unwrap_block_error)?;
+    decompressed.into_inner()?;
 
-    let mut d = ZlibDecoder::new(&*compressed_data);
-    let size = d.read(&mut uncompressed)?;
+    Ok(value)
+}
 
-    Ok(uncompressed[..size].to_vec())
+/// Decompression errors have to pass through [Read](std::io::Read), so turn them back into a [Error].
+pub(crate) fn unwrap_block_error(err: binrw::Error) -> binrw::Error {
+    match err {
+        binrw::Error::Io(err) if err.get_ref().is_some_and(|x| x.is::<Error>()) => {
+            let err = *err.into_inner().unwrap().downcast::<Error>().unwrap();
+            err.into()
+        }
+        binrw::Error::Backtrace(mut backtrace) => {
+            *backtrace.error = unwrap_block_error(*backtrace.error);
+            binrw::Error::Backtrace(backtrace)
+        }
+        err => err,
+    }
 }
 
-// TODO: there's no point in using a parser, we should just use map()
-#[binrw::parser(reader, endian)]
-fn read_tagged_data<T>(data_blocks: &Vec<CompressedBlock>) -> BinResult<TaggedSerialization<T>>
+#[binrw::writer(writer, endian)]
+fn write_tagged_data<T>(
+    value: &TaggedSerialization<T>,
+    options: &CompressionOptions,
+) -> BinResult<()>
 where
     for<'a> T: BinRead<Args<'a> = ()> + 'a,
     for<'a> T: BinWrite<Args<'a> = ()> + 'a,
 {
-    let data_vecs: Vec<Vec<u8>> = data_blocks.iter().map(|x| x.data.clone()).collect();
-    let combined_data = data_vecs.concat();
-    write("output.bin", &combined_data);
-
-    let mut cursor = Cursor::new(&combined_data);
+    let mut uncompressed = Cursor::new(Vec::new());
+    value.write_le(&mut uncompressed)?;
 
-    TaggedSerialization::<T>::read_le(&mut cursor)
-}
-
-#[binrw]
-#[brw(magic = 0x9e2a83c1u32)]
-#[derive(Debug)]
-pub struct CompressedBlock {
-    #[br(pad_before = 6)]
-    #[br(pad_after = 5)]
-    pub unk: u8, // always 2
-    pub a_compresed_size: u64,
-    pub a_uncompresed_size: u64,
-    pub b_compresed_size: u64,
-    pub b_uncompresed_size: u64,
-    #[br(parse_with = read_compressed_data, args(a_compresed_size, a_uncompresed_size))]
-    pub data: Vec<u8>,
+    compression::write_compressed_blocks(uncompressed.get_ref(), writer, endian, (options,))
 }
 
+/// A save file as written by EasyMultiSave, made up of compressed blocks.
+///
+/// Writing this splits `value` into blocks according to `options`, so the result can be loaded again.
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompressedSaveFile<T>
 where
     for<'a> T: BinRead<Args<'a> = ()> + 'a,
     for<'a> T: BinWrite<Args<'a> = ()> + 'a,
 {
-    #[br(parse_with = until_eof)]
-    #[br(temp)]
+    #[br(temp, restore_position)]
+    #[bw(ignore)]
+    layout: CompressionLayout,
+    /// How the file was compressed when read, and how it will be compressed when written.
+    #[br(calc = layout.options())]
     #[bw(ignore)]
-    pub data: Vec<CompressedBlock>,
-    #[br(parse_with = read_tagged_data, args(&data))]
+    pub options: CompressionOptions,
+    #[br(parse_with = read_tagged_data, args(&layout))]
+    #[bw(write_with = write_tagged_data, args(options))]
     pub value: TaggedSerialization<T>,
 }
@@ -1,144 +1,267 @@
 use binrw::{BinRead, BinResult, binrw};
 
 use crate::{
-    common::{read_string_with_length, write_string_with_length},
-    structure::Struct,
+    Error,
+    common::{current_position, read_string_with_length, write_string_with_length},
+    error::in_property,
+    structure::{Guid, ObjectPath, SoftObjectPath, Struct, read_struct},
 };
 
 use super::{
-    PropertyBase,
-    map_property::{KeyType, StringMapKey},
+    PropertyBase, PropertyTag,
+    map_property::{
+        MapSubBoolProperty, MapSubByteProperty, MapSubDoubleProperty, MapSubEnumProperty,
+        MapSubFloatProperty, MapSubInt8Property, MapSubInt16Property, MapSubInt64Property,
+        MapSubIntProperty, MapSubNameProperty, MapSubUInt16Property, MapSubUInt32Property,
+        MapSubUInt64Property, StringMapKey,
+    },
+    property_tag::{read_tag_end, write_tag_end},
 };
 
-fn calculate_header_size(key_name: &str, array_key_data: &ArrayKeyData) -> u64 {
-    // TODO: blah, needs to take into account everything before the first item
-    match array_key_data {
-        ArrayKeyData::String() => 0,
-        ArrayKeyData::Struct {
-            name,
-            type_name,
-            struct_name,
-        } => {
-            // for magic numbers see padding in ArrayKeyData
-            // plus null terminator
-            (name.len() + type_name.len() + struct_name.len() + 18 + 7 + key_name.len() + 1) as u64
-        }
-    }
-}
-
 impl PropertyBase for ArrayProperty {
     fn type_name() -> &'static str {
         "ArrayProperty"
     }
 
     fn size_in_bytes(&self) -> u32 {
-        // todo
-        4 + 8
+        self.tag.tag_size_in_bytes()
             + crate::common::size_of_string_with_length(&self.key_name)
-            + calc_key_data_size_in_bytes(&self.key_data)
             + calc_size_in_bytes(self)
     }
 }
 
 #[binrw::parser(reader, endian)]
 fn custom_parser(
+    start: u64,
     size_in_bytes: u32,
-    key_name: &str,
-    value_type: &KeyType,
-    key_data: &ArrayKeyData,
+    count: u32,
+    value_type: &str,
+    struct_tag: &Option<ArrayStructTag>,
 ) -> BinResult<Vec<ArrayEntry>> {
     let mut result = Vec::<ArrayEntry>::new();
 
-    let mut current = reader.stream_position()?;
-    let end = current + size_in_bytes as u64 - 4 - calculate_header_size(key_name, key_data);
+    let struct_name = struct_tag
+        .as_ref()
+        .map_or("", |struct_tag| struct_tag.struct_name.as_str());
+    // Every element of a native struct is the same size, and bytes are only one byte each if they aren't enum names
+    let pos = reader.stream_position()?;
+    let size_mismatch = || Error::SizeMismatch {
+        pos: start,
+        expected_size: size_in_bytes as u64,
+        actual_size: pos - start,
+    };
+    let entries_size = (start + size_in_bytes as u64)
+        .checked_sub(pos)
+        .ok_or_else(size_mismatch)?;
+    let element_size = u32::try_from(entries_size)
+        .map_err(|_| size_mismatch())?
+        .checked_div(count);
+
+    for i in 0..count {
+        let pos = reader.stream_position()?;
+        let entry =
+            ArrayEntry::read_options(reader, endian, (value_type, struct_name, element_size));
+        result.push(in_property(entry, &format!("[{i}]"), pos)?);
+    }
 
-    while current < end {
-        result.push(ArrayEntry::read_options(
-            reader,
-            endian,
-            (value_type, key_data),
-        )?);
-        current = reader.stream_position()?;
+    let end = reader.stream_position()?;
+    if end - start != size_in_bytes as u64 {
+        return Err(Error::SizeMismatch {
+            pos: start,
+            expected_size: size_in_bytes as u64,
+            actual_size: end - start,
+        }
+        .into());
     }
     Ok(result)
 }
 
 #[binrw]
 #[derive(Debug)]
-#[br(import { magic: &KeyType, key_data: &ArrayKeyData })]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import { magic: &str, struct_name: &str, element_size: Option<u32> })]
+#[allow(clippy::large_enum_variant)]
 pub enum ArrayValue {
-    #[br(pre_assert(KeyType::ArrayStruct == *magic))]
+    #[br(pre_assert("StructProperty" == magic))]
     Struct {
-        #[br(args { magic: match key_data { ArrayKeyData::Struct{ struct_name, .. } => { struct_name }, _ => { "" }} })]
+        #[br(parse_with = read_struct, args(struct_name, element_size))]
         r#struct: Struct,
     },
-    #[br(pre_assert(KeyType::String == *magic))]
+    #[br(pre_assert("StrProperty" == magic))]
     String(StringMapKey),
+    #[br(pre_assert("NameProperty" == magic))]
+    Name(MapSubNameProperty),
+    #[br(pre_assert("EnumProperty" == magic))]
+    Enum(MapSubEnumProperty),
+    #[br(pre_assert("ObjectProperty" == magic))]
+    Object(ObjectPath),
+    #[br(pre_assert("SoftObjectProperty" == magic))]
+    SoftObject(SoftObjectPath),
+    #[br(pre_assert("IntProperty" == magic))]
+    Int(MapSubIntProperty),
+    #[br(pre_assert("Int8Property" == magic))]
+    Int8(MapSubInt8Property),
+    #[br(pre_assert("Int16Property" == magic))]
+    Int16(MapSubInt16Property),
+    #[br(pre_assert("Int64Property" == magic))]
+    Int64(MapSubInt64Property),
+    #[br(pre_assert("UInt16Property" == magic))]
+    UInt16(MapSubUInt16Property),
+    #[br(pre_assert("UInt32Property" == magic))]
+    UInt32(MapSubUInt32Property),
+    #[br(pre_assert("UInt64Property" == magic))]
+    UInt64(MapSubUInt64Property),
+    #[br(pre_assert("DoubleProperty" == magic))]
+    Double(MapSubDoubleProperty),
+    #[br(pre_assert("FloatProperty" == magic))]
+    Float(MapSubFloatProperty),
+    #[br(pre_assert("BoolProperty" == magic))]
+    Bool(MapSubBoolProperty),
+    #[br(pre_assert("ByteProperty" == magic))]
+    Byte(#[br(args { is_enum: element_size != Some(1) })] MapSubByteProperty),
+}
+
+impl ArrayValue {
+    /// Whether `type_name` is one of the property types we know how to read in an array.
+    pub fn is_known(type_name: &str) -> bool {
+        [
+            "StructProperty",
+            "StrProperty",
+            "NameProperty",
+            "EnumProperty",
+            "ObjectProperty",
+            "SoftObjectProperty",
+            "IntProperty",
+            "Int8Property",
+            "Int16Property",
+            "Int64Property",
+            "UInt16Property",
+            "UInt32Property",
+            "UInt64Property",
+            "DoubleProperty",
+            "FloatProperty",
+            "BoolProperty",
+            "ByteProperty",
+        ]
+        .contains(&type_name)
+    }
+}
+
+/// Reads an [ArrayValue], and errors out with a useful message if we don't know about `type_name`.
+#[binrw::parser(reader, endian)]
+fn read_array_value(
+    type_name: &str,
+    struct_name: &str,
+    element_size: Option<u32>,
+) -> BinResult<ArrayValue> {
+    let pos = reader.stream_position()?;
+    if !ArrayValue::is_known(type_name) {
+        return Err(Error::UnknownPropertyType {
+            pos,
+            type_name: type_name.to_string(),
+        }
+        .into());
+    }
+    ArrayValue::read_options(
+        reader,
+        endian,
+        binrw::args! { magic: type_name, struct_name: struct_name, element_size: element_size },
+    )
 }
 
 #[binrw]
 #[derive(Debug)]
-#[br(import(value_type: &KeyType, key_data: &ArrayKeyData))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import(value_type: &str, struct_name: &str, element_size: Option<u32>))]
 pub struct ArrayEntry {
-    #[br(args { magic: value_type, key_data: key_data })]
+    #[br(parse_with = read_array_value, args(value_type, struct_name, element_size))]
     pub key: ArrayValue,
 }
 
+fn calc_value_size_in_bytes(value: &ArrayValue) -> u32 {
+    match value {
+        ArrayValue::Struct { r#struct } => crate::structure::calc_size_in_bytes(r#struct),
+        ArrayValue::String(string_map_key) => {
+            crate::common::size_of_string_with_length(&string_map_key.value)
+        }
+        ArrayValue::Name(map_sub_name_property) => {
+            crate::common::size_of_string_with_length(&map_sub_name_property.value)
+        }
+        ArrayValue::Enum(map_sub_enum_property) => {
+            crate::common::size_of_string_with_length(&map_sub_enum_property.value)
+        }
+        ArrayValue::Object(path) => path.size_in_bytes(),
+        ArrayValue::SoftObject(path) => path.size_in_bytes(),
+        ArrayValue::Int(_) => 4,
+        ArrayValue::Int8(_) => 1,
+        ArrayValue::Int16(_) => 2,
+        ArrayValue::Int64(_) => 8,
+        ArrayValue::UInt16(_) => 2,
+        ArrayValue::UInt32(_) => 4,
+        ArrayValue::UInt64(_) => 8,
+        ArrayValue::Double(_) => 8,
+        ArrayValue::Float(_) => 4,
+        ArrayValue::Bool(_) => 1,
+        ArrayValue::Byte(map_sub_byte_property) => map_sub_byte_property.value.size_in_bytes(),
+    }
+}
+
+fn calc_entries_size_in_bytes(prop: &ArrayProperty) -> u32 {
+    prop.entries
+        .iter()
+        .map(|entry| calc_value_size_in_bytes(&entry.key))
+        .sum()
+}
+
 fn calc_size_in_bytes(prop: &ArrayProperty) -> u32 {
-    // TODO: complete guesswork still
-    let mut size = 4;
-
-    for entry in &prop.entries {
-        size += match &entry.key {
-            ArrayValue::Struct { r#struct } => crate::structure::calc_size_in_bytes(r#struct),
-            ArrayValue::String(string_map_key) => {
-                crate::common::size_of_string_with_length(&string_map_key.value)
-            }
-        };
+    // 4 bytes for the entry count
+    let mut size = 4 + calc_entries_size_in_bytes(prop);
+
+    if let Some(struct_tag) = &prop.struct_tag {
+        size += crate::common::size_of_string_with_length(&struct_tag.name)
+            + crate::common::size_of_string_with_length(&struct_tag.type_name)
+            + crate::common::size_of_string_with_length(&struct_tag.struct_name)
+            + struct_tag.tag.tag_size_in_bytes();
     }
 
     size
 }
 
+/// The tag that comes before the elements of an array of structs, so they don't each need one.
 #[binrw]
 #[derive(Debug)]
-#[br(import { key_type: &KeyType })]
-pub enum ArrayKeyData {
-    #[br(pre_assert(*key_type == KeyType::String))]
-    String(),
-    #[br(pre_assert(*key_type == KeyType::ArrayStruct))]
-    Struct {
-        #[br(parse_with = read_string_with_length)]
-        #[bw(write_with = write_string_with_length)]
-        name: String,
-
-        #[br(parse_with = read_string_with_length)]
-        #[bw(write_with = write_string_with_length)]
-        type_name: String,
-
-        #[br(parse_with = read_string_with_length)]
-        #[bw(write_with = write_string_with_length)]
-        #[brw(pad_before = 8)] // idk
-        #[brw(pad_after = 17)] // super idk
-        struct_name: String,
-    },
-}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[bw(import(entries_size_in_bytes: u32))]
+pub struct ArrayStructTag {
+    /// Usually the same as the name of the array.
+    #[br(parse_with = read_string_with_length)]
+    #[bw(write_with = write_string_with_length)]
+    pub name: String,
 
-fn calc_key_data_size_in_bytes(key_data: &ArrayKeyData) -> u32 {
-    match key_data {
-        ArrayKeyData::String() => 0,
-        ArrayKeyData::Struct {
-            name,
-            type_name,
-            struct_name,
-        } => {
-            crate::common::size_of_string_with_length(name)
-                + crate::common::size_of_string_with_length(type_name)
-                + crate::common::size_of_string_with_length(struct_name)
-                + 8
-                + 17
-        }
-    }
+    #[br(parse_with = read_string_with_length)]
+    #[bw(write_with = write_string_with_length)]
+    pub type_name: String,
+
+    // Size of all the elements together
+    #[br(temp)]
+    #[bw(calc = entries_size_in_bytes)]
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
+
+    #[br(parse_with = read_string_with_length)]
+    #[bw(write_with = write_string_with_length)]
+    pub struct_name: String,
+
+    #[br(temp)]
+    #[bw(calc = tag.struct_guid.unwrap_or_default())]
+    struct_guid: Guid,
+
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, Some(struct_guid)))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
 }
 
 /// An array.
@@ -146,28 +269,45 @@ fn calc_key_data_size_in_bytes(key_data: &ArrayKeyData) -> u32 {
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UArrayProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ArrayProperty {
+    #[br(temp)]
     #[bw(calc = calc_size_in_bytes(self))]
-    pub size_in_bytes: u32,
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
 
-    #[brw(pad_before = 4)]
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
     pub key_name: String,
 
-    #[brw(pad_before = 1)]
-    pub key_type: KeyType,
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, None))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
+
+    #[br(temp, parse_with = current_position)]
+    #[bw(ignore)]
+    start: u64,
 
-    #[br(args { key_type: &key_type })]
-    pub key_data: ArrayKeyData,
+    #[br(temp)]
+    #[bw(calc = entries.len() as u32)]
+    num_entries: u32,
 
-    #[br(parse_with = custom_parser, args(size_in_bytes, &key_name, &key_type, &key_data))]
+    /// Only arrays of structs have this.
+    #[br(if(key_name == "StructProperty"))]
+    #[bw(args(calc_entries_size_in_bytes(self)))]
+    pub struct_tag: Option<ArrayStructTag>,
+
+    #[br(parse_with = custom_parser, args(start, size_in_bytes, num_entries, &key_name, &struct_tag))]
     pub entries: Vec<ArrayEntry>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::property::ByteValue;
     use binrw::{BinRead, BinWrite};
     use std::io::Cursor;
 
@@ -189,6 +329,27 @@ mod tests {
         assert_eq!(value_property.value, "redstrate");
     }
 
+    #[test]
+    fn read_array_too_small() {
+        // Too small to even have the element count in it
+        let data = [
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x53, 0x74,
+            0x72, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x01, 0x00, 0x00,
+            0x00, 0x0a, 0x00, 0x00, 0x00, 0x72, 0x65, 0x64, 0x73, 0x74, 0x72, 0x61, 0x74, 0x65,
+            0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let err = ArrayProperty::read_le(&mut cursor).unwrap_err();
+        assert_eq!(
+            err.custom_err::<Error>(),
+            Some(&Error::SizeMismatch {
+                pos: 25,
+                expected_size: 2,
+                actual_size: 4,
+            })
+        );
+    }
+
     #[test]
     fn write_simple_array() {
         let expected_data: [u8; 43] = [
@@ -198,9 +359,9 @@ mod tests {
             0x00,
         ];
         let property = ArrayProperty {
+            tag: PropertyTag::default(),
             key_name: "StrProperty".to_string(),
-            key_type: KeyType::String,
-            key_data: ArrayKeyData::String(),
+            struct_tag: None,
             entries: vec![ArrayEntry {
                 key: ArrayValue::String(StringMapKey {
                     value: "redstrate".to_string(),
@@ -215,6 +376,7 @@ mod tests {
         }
 
         assert_eq!(expected_data, &buffer[..]);
+        assert_eq!(property.size_in_bytes(), buffer.len() as u32);
     }
 
     #[test]
@@ -382,5 +544,135 @@ mod tests {
         let decoded = ArrayProperty::read_le(&mut cursor).unwrap();
         assert_eq!(decoded.key_name, "StructProperty");
         assert_eq!(decoded.entries.len(), 10);
+        assert_eq!(decoded.struct_tag.as_ref().unwrap().name, "ItemSlots");
+        assert_eq!(
+            decoded.struct_tag.as_ref().unwrap().struct_name,
+            "PrimaryAssetId"
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn roundtrip_int_array() {
+        let data = [
+            0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x49, 0x6e,
+            0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x03, 0x00, 0x00,
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ArrayProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.key_name, "IntProperty");
+        assert_eq!(decoded.entries.len(), 3);
+        let ArrayValue::Int(value_property) = &decoded.entries[2].key else {
+            panic!("IntProperty!")
+        };
+        assert_eq!(value_property.value, 3);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn roundtrip_int64_array() {
+        let data = [
+            0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0e, 0x00, 0x00, 0x00, 0x49, 0x6e,
+            0x74, 0x36, 0x34, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x02,
+            0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x01, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ArrayProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.key_name, "Int64Property");
+        let ArrayValue::Int64(first) = &decoded.entries[0].key else {
+            panic!("Int64Property!")
+        };
+        assert_eq!(first.value, -1);
+        let ArrayValue::Int64(second) = &decoded.entries[1].key else {
+            panic!("Int64Property!")
+        };
+        assert_eq!(second.value, 1 << 40);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn roundtrip_enum_byte_array() {
+        let data = [
+            0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x42, 0x79,
+            0x74, 0x65, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x02, 0x00,
+            0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x45, 0x44, 0x41, 0x53, 0x74, 0x61, 0x74, 0x65,
+            0x3a, 0x3a, 0x41, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x45, 0x44, 0x41, 0x53, 0x74, 0x61,
+            0x74, 0x65, 0x3a, 0x3a, 0x42, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ArrayProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.key_name, "ByteProperty");
+        let ArrayValue::Byte(second) = &decoded.entries[1].key else {
+            panic!("ByteProperty!")
+        };
+        assert_eq!(second.value, ByteValue::Enum("EDAState::B".to_string()));
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn roundtrip_guid_array() {
+        let data = [
+            0x61, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x53, 0x74,
+            0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x49, 0x64, 0x73, 0x00, 0x0f, 0x00,
+            0x00, 0x00, 0x53, 0x74, 0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72,
+            0x74, 0x79, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00,
+            0x00, 0x47, 0x75, 0x69, 0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05,
+            0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13,
+            0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ArrayProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.entries.len(), 2);
+        let ArrayValue::Struct {
+            r#struct: Struct::Guid(guid),
+        } = &decoded.entries[1].key
+        else {
+            panic!("Guid!")
+        };
+        assert_eq!(guid.a, 0x14131211);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
     }
 }
@@ -1,27 +1,43 @@
 use crate::common::{read_bool_from, write_bool_as};
 use binrw::binrw;
 
-use super::PropertyBase;
+use super::{
+    PropertyBase, PropertyTag,
+    property_tag::{read_tag_end, write_tag_end},
+};
 
 /// A boolean.
 ///
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UBoolProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoolProperty {
-    #[brw(pad_before = 8, pad_after = 1)]
+    #[br(temp)]
+    #[bw(calc = 0)]
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
+
+    // The value is part of the tag, so the size is always zero
     #[br(map = read_bool_from::<u8>)]
     #[bw(map = write_bool_as::<u8>)]
     pub value: bool,
+
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, None))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
 }
 
 impl PropertyBase for BoolProperty {
     fn type_name() -> &'static str {
-        "IntProperty"
+        "BoolProperty"
     }
 
     fn size_in_bytes(&self) -> u32 {
-        10
+        self.tag.tag_size_in_bytes() + 1
     }
 }
 
@@ -42,7 +58,10 @@ mod tests {
     #[test]
     fn write_false() {
         let expected_data: [u8; 10] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
-        let property = BoolProperty { value: false };
+        let property = BoolProperty {
+            value: false,
+            tag: PropertyTag::default(),
+        };
 
         let mut buffer: Vec<u8> = Vec::new();
         {
@@ -64,7 +83,10 @@ mod tests {
     #[test]
     fn write_true() {
         let expected_data: [u8; 10] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00];
-        let property = BoolProperty { value: true };
+        let property = BoolProperty {
+            value: true,
+            tag: PropertyTag::default(),
+        };
 
         let mut buffer: Vec<u8> = Vec::new();
         {
@@ -0,0 +1,152 @@
+use crate::common::{read_string_with_length, write_string_with_length};
+use binrw::binrw;
+
+use super::{
+    PropertyBase, PropertyTag,
+    property_tag::{read_tag_end, write_tag_end},
+};
+
+/// The value of a byte, which is either a plain number or the name of an enum value.
+#[binrw]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import { is_enum: bool })]
+pub enum ByteValue {
+    #[br(pre_assert(!is_enum))]
+    Byte(u8),
+    #[br(pre_assert(is_enum))]
+    Enum(
+        #[br(parse_with = read_string_with_length)]
+        #[bw(write_with = write_string_with_length)]
+        String,
+    ),
+}
+
+impl ByteValue {
+    pub(crate) fn size_in_bytes(&self) -> u32 {
+        match self {
+            ByteValue::Byte(_) => 1,
+            ByteValue::Enum(name) => crate::common::size_of_string_with_length(name),
+        }
+    }
+}
+
+/// A value of an enum, like `EDAWeaponModulePosition::FrontWeapon`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumValue {
+    /// Name of the enum, like `EDAWeaponModulePosition`.
+    pub enum_type: String,
+    /// Name of the value, including the enum type in front of it.
+    pub name: String,
+}
+
+/// A byte, or an enum that's small enough to fit in one.
+///
+/// Enums are stored by the name of their value, not as a number.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UByteProperty?application_version=4.27).
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ByteProperty {
+    #[br(temp)]
+    #[bw(calc = value.size_in_bytes())]
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
+
+    /// Name of the enum, or `None` if this is a plain byte.
+    #[br(parse_with = read_string_with_length)]
+    #[bw(write_with = write_string_with_length)]
+    pub enum_type: String,
+
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, None))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
+
+    #[br(args { is_enum: enum_type != "None" })]
+    pub value: ByteValue,
+}
+
+impl ByteProperty {
+    /// The enum value this is set to, or `None` if this is a plain byte.
+    pub fn enum_value(&self) -> Option<EnumValue> {
+        match &self.value {
+            ByteValue::Byte(_) => None,
+            ByteValue::Enum(name) => Some(EnumValue {
+                enum_type: self.enum_type.clone(),
+                name: name.clone(),
+            }),
+        }
+    }
+}
+
+impl PropertyBase for ByteProperty {
+    fn type_name() -> &'static str {
+        "ByteProperty"
+    }
+
+    fn size_in_bytes(&self) -> u32 {
+        self.tag.tag_size_in_bytes()
+            + crate::common::size_of_string_with_length(&self.enum_type)
+            + self.value.size_in_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::{BinRead, BinWrite};
+    use std::io::Cursor;
+
+    #[test]
+    fn roundtrip_byte() {
+        let data = [
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x4e, 0x6f,
+            0x6e, 0x65, 0x00, 0x00, 0x2a,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ByteProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.value, ByteValue::Byte(42));
+        assert_eq!(decoded.enum_value(), None);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn roundtrip_enum() {
+        let data = [
+            0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x45, 0x44,
+            0x41, 0x53, 0x74, 0x61, 0x74, 0x65, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x45, 0x44,
+            0x41, 0x53, 0x74, 0x61, 0x74, 0x65, 0x3a, 0x3a, 0x41, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ByteProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(
+            decoded.enum_value(),
+            Some(EnumValue {
+                enum_type: "EDAState".to_string(),
+                name: "EDAState::A".to_string(),
+            })
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+}
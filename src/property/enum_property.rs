@@ -1,20 +1,34 @@
 use crate::common::{read_string_with_length, write_string_with_length};
 use binrw::binrw;
 
-use super::PropertyBase;
+use super::{
+    PropertyBase, PropertyTag,
+    property_tag::{read_tag_end, write_tag_end},
+};
 
 /// A enum.
 ///
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UEnumProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnumProperty {
-    #[br(pad_before = 8)] // unk
+    #[br(temp)]
+    #[bw(calc = crate::common::size_of_string_with_length(value))]
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
+
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
     pub enum_type: String,
 
-    #[br(pad_before = 1)]
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, None))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
+
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
     pub value: String,
@@ -26,8 +40,8 @@ impl PropertyBase for EnumProperty {
     }
 
     fn size_in_bytes(&self) -> u32 {
-        8 + crate::common::size_of_string_with_length(&self.enum_type)
-            + 1
+        self.tag.tag_size_in_bytes()
+            + crate::common::size_of_string_with_length(&self.enum_type)
             + crate::common::size_of_string_with_length(&self.value)
     }
 }
@@ -35,7 +49,7 @@ impl PropertyBase for EnumProperty {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use binrw::BinRead;
+    use binrw::{BinRead, BinWrite};
     use std::io::Cursor;
 
     #[test]
@@ -53,5 +67,14 @@ mod tests {
         let decoded = EnumProperty::read_le(&mut cursor).unwrap();
         assert_eq!(decoded.enum_type, "EDAWeaponModulePosition");
         assert_eq!(decoded.value, "EDAWeaponModulePosition::FrontWeapon");
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
     }
 }
@@ -1,16 +1,16 @@
 use binrw::binrw;
 
-use super::PropertyBase;
+use super::{PropertyBase, PropertyTag};
 
 /// A float.
 ///
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UFloatProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FloatProperty {
-    #[bw(calc = 4)]
-    pub size_in_bytes: u32,
-    #[brw(pad_before = 5)]
+    #[bw(args(4))]
+    pub tag: PropertyTag,
     pub value: f32,
 }
 
@@ -20,7 +20,7 @@ impl PropertyBase for FloatProperty {
     }
 
     fn size_in_bytes(&self) -> u32 {
-        4 + 5 + 4
+        self.tag.tag_size_in_bytes() + 4
     }
 }
 
@@ -45,7 +45,10 @@ mod tests {
         let expected_data: [u8; 13] = [
             0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x8c, 0x76, 0x9c, 0x45,
         ];
-        let property = FloatProperty { value: 5006.8184 };
+        let property = FloatProperty {
+            tag: PropertyTag::default(),
+            value: 5006.8184,
+        };
 
         let mut buffer: Vec<u8> = Vec::new();
         {
@@ -1,13 +1,32 @@
-use binrw::binrw;
+use std::io::{Read, Seek, SeekFrom};
+
+use binrw::{BinRead, BinResult, BinWrite, binrw};
 
 use crate::{
     common::{read_string_with_length, write_string_with_length},
-    save_object::generic::Property,
+    error::in_property,
+    save_object::generic::{Property, read_property, write_property},
 };
 
+#[binrw::parser(reader, endian)]
+fn read_boxed_property(type_name: &str, name: &str) -> BinResult<Option<Box<Property>>> {
+    let pos = reader.stream_position()?;
+    in_property(read_property(reader, endian, (type_name, name)), name, pos)
+        .map(|x| Some(Box::new(x)))
+}
+
+#[binrw::writer(writer, endian)]
+fn write_boxed_property(property: &Option<Box<Property>>, type_name: &str) -> BinResult<()> {
+    match property {
+        Some(property) => write_property(property, writer, endian, (type_name,)),
+        None => Ok(()),
+    }
+}
+
 /// A generic property that has no name or type requirements.
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericProperty {
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
@@ -15,10 +34,82 @@ pub struct GenericProperty {
 
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
-    #[br(if(property_name != "None"))]
+    #[brw(if(property_name != "None"))]
     pub type_name: String,
 
     #[br(if(property_name != "None"))]
-    #[br(args { magic: &type_name, name: &property_name})]
+    #[br(parse_with = read_boxed_property, args(&type_name, &property_name))]
+    #[bw(write_with = write_boxed_property, args(type_name))]
     pub key: Option<Box<Property>>,
 }
+
+/// Reads properties up to and including the "None" that ends the list.
+#[binrw::parser(reader, endian)]
+pub(crate) fn read_properties_until_none() -> BinResult<Vec<GenericProperty>> {
+    let mut result = Vec::<GenericProperty>::new();
+
+    loop {
+        let property = GenericProperty::read_options(reader, endian, ())?;
+        if property.property_name == "None" {
+            break;
+        }
+        result.push(property);
+    }
+    Ok(result)
+}
+
+/// Writes properties followed by the "None" that ends the list.
+// binrw hands write_with the field by reference, so this has to take a &Vec
+#[allow(clippy::ptr_arg)]
+#[binrw::writer(writer, endian)]
+pub(crate) fn write_properties_until_none(properties: &Vec<GenericProperty>) -> BinResult<()> {
+    properties.write_options(writer, endian, ())?;
+    let none = GenericProperty {
+        property_name: "None".to_string(),
+        type_name: "".to_string(),
+        key: None,
+    };
+    none.write_options(writer, endian, ())
+}
+
+/// Whether the data at the current position looks like a list of tagged properties, without moving the reader.
+///
+/// Native structs (like [Guid](crate::structure::Guid)) are just their bytes, so this checks for a property name followed by a type name.
+pub(crate) fn looks_like_properties<R: Read + Seek>(reader: &mut R) -> BinResult<bool> {
+    let pos = reader.stream_position()?;
+    let result = match peek_name(reader) {
+        Some(name) if name == "None" => true,
+        Some(_) => peek_name(reader).is_some_and(|type_name| type_name.ends_with("Property")),
+        None => false,
+    };
+    reader.seek(SeekFrom::Start(pos))?;
+    Ok(result)
+}
+
+/// Whether the data at the current position looks like an FName, without moving the reader.
+pub(crate) fn looks_like_name<R: Read + Seek>(reader: &mut R) -> BinResult<bool> {
+    let pos = reader.stream_position()?;
+    let result = peek_name(reader).is_some();
+    reader.seek(SeekFrom::Start(pos))?;
+    Ok(result)
+}
+
+/// Reads something that could be an FName, and gives up early if it's clearly not one.
+fn peek_name<R: Read + Seek>(reader: &mut R) -> Option<String> {
+    let length = u32::read_le(reader).ok()?;
+    // Names are never empty or this long, and it's not worth allocating for garbage
+    if !(2..=1024).contains(&length) {
+        return None;
+    }
+    let mut bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut bytes).ok()?;
+    // Only what an identifier or an enum value like `EDAState::A` can have, so plain bytes that happen to end in a zero don't count
+    if bytes.pop() != Some(0)
+        || !bytes
+            .iter()
+            .all(|x| x.is_ascii_alphanumeric() || *x == b'_' || *x == b':')
+    {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
@@ -1,17 +1,17 @@
 use binrw::binrw;
 
-use super::PropertyBase;
+use super::{PropertyBase, PropertyTag};
 
-/// A integer.
+/// A signed 32-bit integer.
 ///
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UIntProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntProperty {
-    #[bw(calc = 4)]
-    pub size_in_bytes: u32,
-    #[brw(pad_before = 5)]
-    pub value: u32,
+    #[bw(args(4))]
+    pub tag: PropertyTag,
+    pub value: i32,
 }
 
 impl PropertyBase for IntProperty {
@@ -20,7 +20,7 @@ impl PropertyBase for IntProperty {
     }
 
     fn size_in_bytes(&self) -> u32 {
-        4 + 5 + 4
+        self.tag.tag_size_in_bytes() + 4
     }
 }
 
@@ -45,7 +45,10 @@ mod tests {
         let expected_data: [u8; 13] = [
             0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         ];
-        let property = IntProperty { value: 0 };
+        let property = IntProperty {
+            tag: PropertyTag::default(),
+            value: 0,
+        };
 
         let mut buffer: Vec<u8> = Vec::new();
         {
@@ -66,12 +69,25 @@ mod tests {
         assert_eq!(decoded.value, 4);
     }
 
+    #[test]
+    fn negative_integer() {
+        let data = [
+            0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = IntProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.value, -1);
+    }
+
     #[test]
     fn write_integer() {
         let expected_data: [u8; 13] = [
             0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
         ];
-        let property = IntProperty { value: 4 };
+        let property = IntProperty {
+            tag: PropertyTag::default(),
+            value: 4,
+        };
 
         let mut buffer: Vec<u8> = Vec::new();
         {
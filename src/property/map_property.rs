@@ -1,70 +1,42 @@
+use std::io::{Read, Seek, SeekFrom};
+
 use binrw::{BinRead, BinResult, binrw};
 
 use crate::{
-    common::{read_bool_from, read_string_with_length, write_bool_as, write_string_with_length},
-    structure::{Guid, Struct},
+    Error,
+    common::{
+        current_position, read_bool_from, read_string_with_length, write_bool_as,
+        write_string_with_length,
+    },
+    error::in_property,
+    structure::{Guid, ObjectPath, SoftObjectPath},
 };
 
-use super::{GenericProperty, PropertyBase};
-
-// parse until we can't parse no more. kind of a hack for how we run into the end of Persistent.Sav
-#[binrw::parser(reader, endian)]
-fn cc() -> BinResult<Vec<GenericProperty>> {
-    let mut result = Vec::<GenericProperty>::new();
-
-    loop {
-        if let Ok(str) = GenericProperty::read_options(reader, endian, ()) {
-            if str.property_name == "None" {
-                break;
-            }
-            result.push(str);
-        } else {
-            break;
-        }
-    }
-    Ok(result)
-}
+use super::{
+    ByteValue, GenericProperty, PropertyBase, PropertyTag, looks_like_name, looks_like_properties,
+    property_tag::{read_tag_end, write_tag_end},
+    read_properties_until_none, write_properties_until_none,
+};
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapSubStructProperty {
-    #[br(parse_with = cc)]
-    fields: Vec<GenericProperty>,
-}
-
-#[binrw]
-#[derive(Debug)]
-pub struct StructMaybeKey {
-    #[br(parse_with = read_string_with_length)]
-    #[bw(write_with = write_string_with_length)]
-    pub unk_name: String,
-
-    #[brw(pad_after = 8)]
-    #[br(parse_with = read_string_with_length)]
-    #[bw(write_with = write_string_with_length)]
-    pub unk_type: String,
-
-    #[br(parse_with = read_string_with_length)]
-    #[bw(write_with = write_string_with_length)]
-    pub struct_name: String,
-
-    #[br(args { magic: &struct_name })]
-    #[brw(pad_before = 17)]
-    pub r#struct: Struct,
-
-    #[br(parse_with = cc)]
-    #[br(dbg)]
-    extra_fields: Vec<GenericProperty>,
+    #[br(parse_with = read_properties_until_none)]
+    #[bw(write_with = write_properties_until_none)]
+    pub fields: Vec<GenericProperty>,
 }
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapSubFloatProperty {
     pub value: f32,
 }
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapSubNameProperty {
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
@@ -73,6 +45,7 @@ pub struct MapSubNameProperty {
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapSubStrProperty {
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
@@ -81,6 +54,7 @@ pub struct MapSubStrProperty {
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapSubBoolProperty {
     #[br(map = read_bool_from::<u8>)]
     #[bw(map = write_bool_as::<u8>)]
@@ -89,27 +63,95 @@ pub struct MapSubBoolProperty {
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapSubIntProperty {
+    pub value: i32,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSubInt8Property {
+    pub value: i8,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSubInt16Property {
+    pub value: i16,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSubInt64Property {
+    pub value: i64,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSubUInt16Property {
+    pub value: u16,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSubUInt32Property {
     pub value: u32,
 }
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSubUInt64Property {
+    pub value: u64,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSubDoubleProperty {
+    pub value: f64,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import { is_enum: bool })]
+pub struct MapSubByteProperty {
+    #[br(args { is_enum })]
+    pub value: ByteValue,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapSubEnumProperty {
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
     pub value: String,
 }
 
-// Used in MapProperty exclusively, seems to be a shortened version of some Properties
+// Used in MapProperty and SetProperty, these are Properties without any tag in front of them
 #[binrw]
 #[derive(Debug)]
-#[br(import { magic: &str })]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import { magic: &str, tagged: bool, is_enum: bool, struct_size: Option<u32> })]
 pub enum MabSubProperty {
     #[br(pre_assert("NameProperty" == magic))]
     Name(MapSubNameProperty),
-    #[br(pre_assert("StructProperty" == magic))]
+    #[br(pre_assert("StructProperty" == magic && tagged))]
     Struct(MapSubStructProperty),
+    /// A struct that isn't made up of tagged properties and is the size of a GUID. There's no struct name to go off of, so this is
+    /// also used when the size can't be worked out.
+    #[br(pre_assert("StructProperty" == magic && !tagged && struct_size.is_none_or(|size| size == 16)))]
+    Guid(Guid),
+    /// A struct that isn't made up of tagged properties and isn't the size of a GUID, kept as-is.
+    #[br(pre_assert("StructProperty" == magic && !tagged && struct_size.is_some_and(|size| size != 16)))]
+    RawStruct(#[br(count = struct_size.unwrap_or_default())] Vec<u8>),
     #[br(pre_assert("FloatProperty" == magic))]
     Float(MapSubFloatProperty),
     #[br(pre_assert("StrProperty" == magic))]
@@ -118,12 +160,198 @@ pub enum MabSubProperty {
     Bool(MapSubBoolProperty),
     #[br(pre_assert("IntProperty" == magic))]
     Int(MapSubIntProperty),
+    #[br(pre_assert("Int8Property" == magic))]
+    Int8(MapSubInt8Property),
+    #[br(pre_assert("Int16Property" == magic))]
+    Int16(MapSubInt16Property),
+    #[br(pre_assert("Int64Property" == magic))]
+    Int64(MapSubInt64Property),
+    #[br(pre_assert("UInt16Property" == magic))]
+    UInt16(MapSubUInt16Property),
+    #[br(pre_assert("UInt32Property" == magic))]
+    UInt32(MapSubUInt32Property),
+    #[br(pre_assert("UInt64Property" == magic))]
+    UInt64(MapSubUInt64Property),
+    #[br(pre_assert("DoubleProperty" == magic))]
+    Double(MapSubDoubleProperty),
     #[br(pre_assert("EnumProperty" == magic))]
     Enum(MapSubEnumProperty),
+    #[br(pre_assert("ObjectProperty" == magic))]
+    Object(ObjectPath),
+    #[br(pre_assert("SoftObjectProperty" == magic))]
+    SoftObject(SoftObjectPath),
+    /// Bytes that are enums are stored as names, but there's nothing saying which ones are so this looks ahead for one.
+    #[br(pre_assert("ByteProperty" == magic))]
+    Byte(#[br(args { is_enum })] MapSubByteProperty),
+}
+
+impl MabSubProperty {
+    /// Whether `type_name` is one of the property types we know how to read in a map or set.
+    pub fn is_known(type_name: &str) -> bool {
+        [
+            "NameProperty",
+            "StructProperty",
+            "FloatProperty",
+            "StrProperty",
+            "BoolProperty",
+            "IntProperty",
+            "Int8Property",
+            "Int16Property",
+            "Int64Property",
+            "UInt16Property",
+            "UInt32Property",
+            "UInt64Property",
+            "DoubleProperty",
+            "EnumProperty",
+            "ObjectProperty",
+            "SoftObjectProperty",
+            "ByteProperty",
+        ]
+        .contains(&type_name)
+    }
+}
+
+/// How many bytes each element of `type_name` takes up in a map or set, if that's always the same.
+fn fixed_sub_size_in_bytes(type_name: &str) -> Option<u32> {
+    match type_name {
+        "BoolProperty" | "Int8Property" => Some(1),
+        "Int16Property" | "UInt16Property" => Some(2),
+        "FloatProperty" | "IntProperty" | "UInt32Property" => Some(4),
+        "Int64Property" | "UInt64Property" | "DoubleProperty" => Some(8),
+        _ => None,
+    }
+}
+
+/// The most bytes an untagged struct in a map or set is expected to take up, when looking for its size.
+const MAX_STRUCT_SIZE: u32 = 256;
+
+/// Works out how big the untagged structs in a map or set are from its size and counts, since there's no struct name to go off of.
+///
+/// The reader has to be right after the number of removed elements, and is put back there. Each entry is a struct followed by
+/// `other_size` bytes (0 for a set), and each removed element is `removed_size` bytes, or a struct if that's `None`. Gives `None`
+/// if no struct size adds up.
+pub(crate) fn find_struct_size<R: Read + Seek>(
+    reader: &mut R,
+    size_in_bytes: u32,
+    num_removed: u32,
+    removed_size: Option<u32>,
+    other_size: u32,
+) -> BinResult<Option<u32>> {
+    let pos = reader.stream_position()?;
+    // 4 bytes for the number of removed elements, and 4 bytes for the entry count
+    let entries_size = (size_in_bytes as u64).saturating_sub(4 + 4);
+    let num_removed = num_removed as u64;
+    let other_size = other_size as u64;
+
+    let mut read_num_entries = |removed_size: u64| -> BinResult<Option<u64>> {
+        reader.seek(SeekFrom::Start(pos + num_removed * removed_size))?;
+        Ok(u32::read_le(reader).ok().map(u64::from))
+    };
+
+    let mut result = None;
+    if num_removed == 0 || removed_size.is_some() {
+        let removed_size = removed_size.unwrap_or_default() as u64;
+        if let Some(num_entries) = read_num_entries(removed_size)?.filter(|x| *x > 0)
+            && let Some(structs_size) =
+                entries_size.checked_sub(num_removed * removed_size + num_entries * other_size)
+            && structs_size % num_entries == 0
+        {
+            result = u32::try_from(structs_size / num_entries).ok();
+        }
+    } else {
+        // The removed structs are in front of the entry count, so try every size until one adds up
+        for struct_size in 1..=MAX_STRUCT_SIZE as u64 {
+            if let Some(num_entries) = read_num_entries(struct_size)?
+                && num_removed * struct_size + num_entries * (struct_size + other_size)
+                    == entries_size
+            {
+                result = Some(struct_size as u32);
+                break;
+            }
+        }
+    }
+
+    reader.seek(SeekFrom::Start(pos))?;
+    Ok(result)
+}
+
+/// Reads a [MabSubProperty], looking ahead to figure out what kind of struct it is.
+///
+/// `struct_size` is the size of an untagged struct, if it's known.
+#[binrw::parser(reader, endian)]
+pub(crate) fn read_sub_property(
+    type_name: &str,
+    struct_size: Option<u32>,
+) -> BinResult<MabSubProperty> {
+    let pos = reader.stream_position()?;
+    if !MabSubProperty::is_known(type_name) {
+        return Err(Error::UnknownPropertyType {
+            pos,
+            type_name: type_name.to_string(),
+        }
+        .into());
+    }
+    let tagged = type_name == "StructProperty" && looks_like_properties(reader)?;
+    let is_enum = type_name == "ByteProperty" && looks_like_name(reader)?;
+    MabSubProperty::read_options(
+        reader,
+        endian,
+        binrw::args! { magic: type_name, tagged: tagged, is_enum: is_enum, struct_size: struct_size },
+    )
+}
+
+/// Reads `count` [MabSubProperty], e.g. the keys to remove from a map.
+#[binrw::parser(reader, endian)]
+pub(crate) fn read_sub_properties(
+    count: u32,
+    type_name: &str,
+    struct_size: Option<u32>,
+) -> BinResult<Vec<MabSubProperty>> {
+    let mut result = Vec::<MabSubProperty>::new();
+
+    for i in 0..count {
+        let pos = reader.stream_position()?;
+        let property = read_sub_property(reader, endian, (type_name, struct_size));
+        result.push(in_property(property, &format!("[{i}]"), pos)?);
+    }
+    Ok(result)
+}
+
+pub(crate) fn calc_sub_size_in_bytes(property: &MabSubProperty) -> u32 {
+    match property {
+        MabSubProperty::Name(map_sub_name_property) => {
+            crate::common::size_of_string_with_length(&map_sub_name_property.value)
+        }
+        MabSubProperty::Struct(map_sub_struct_property) => {
+            crate::structure::calc_properties_size_in_bytes(&map_sub_struct_property.fields)
+        }
+        MabSubProperty::Guid(guid) => guid.size_in_bytes(),
+        MabSubProperty::RawStruct(raw) => raw.len() as u32,
+        MabSubProperty::Float(_) => 4,
+        MabSubProperty::String(map_sub_str_property) => {
+            crate::common::size_of_string_with_length(&map_sub_str_property.value)
+        }
+        MabSubProperty::Bool(_) => 1,
+        MabSubProperty::Int(_) => 4,
+        MabSubProperty::Int8(_) => 1,
+        MabSubProperty::Int16(_) => 2,
+        MabSubProperty::Int64(_) => 8,
+        MabSubProperty::UInt16(_) => 2,
+        MabSubProperty::UInt32(_) => 4,
+        MabSubProperty::UInt64(_) => 8,
+        MabSubProperty::Double(_) => 8,
+        MabSubProperty::Enum(map_sub_enum_property) => {
+            crate::common::size_of_string_with_length(&map_sub_enum_property.value)
+        }
+        MabSubProperty::Object(path) => path.size_in_bytes(),
+        MabSubProperty::SoftObject(path) => path.size_in_bytes(),
+        MabSubProperty::Byte(map_sub_byte_property) => map_sub_byte_property.value.size_in_bytes(),
+    }
 }
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringMapKey {
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
@@ -132,139 +360,87 @@ pub struct StringMapKey {
 
 #[binrw]
 #[derive(Debug)]
-#[br(import { magic: &KeyType })]
-pub enum MapKeyProperty {
-    #[br(pre_assert(*magic == KeyType::String))]
-    String(StringMapKey),
-    #[br(pre_assert(*magic == KeyType::StructMaybe))]
-    StructMaybe(StructMaybeKey),
-    #[br(pre_assert(*magic == KeyType::Enum))]
-    Enum(MapSubEnumProperty),
-    #[br(pre_assert(*magic == KeyType::EnumAgain))]
-    EnumAgain(MapSubEnumProperty),
-    #[br(pre_assert(*magic == KeyType::GUID))]
-    GUID(Guid),
-    #[br(pre_assert(*magic == KeyType::SomeID))]
-    SomeID(Guid),
-    #[br(pre_assert(*magic == KeyType::SomeID2))]
-    SomeID2(Guid),
-    #[br(pre_assert(*magic == KeyType::ArrayStruct))]
-    ArrayStruct(StructMaybeKey),
-    #[br(pre_assert(*magic == KeyType::Unknown2))]
-    SomeID3(Guid),
-    #[br(pre_assert(*magic == KeyType::SoftObjectProperty))]
-    SoftObjectProperty(StringMapKey),
-}
-
-#[binrw]
-#[derive(Debug)]
-#[br(import(key_type: &KeyType, value_type: &str))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import(key_type: &str, value_type: &str, struct_sizes: (Option<u32>, Option<u32>)))]
 pub struct MapEntry {
-    #[br(args { magic: key_type})]
-    pub key: MapKeyProperty,
+    #[br(parse_with = read_sub_property, args(key_type, struct_sizes.0))]
+    pub key: MabSubProperty,
 
-    #[br(args { magic: value_type })]
+    #[br(parse_with = read_sub_property, args(value_type, struct_sizes.1))]
     pub value: MabSubProperty,
 }
 
-// TODO: im worried that set/array/map actually share same of the same values...
-#[binrw]
-#[brw(repr = u32)]
-#[derive(Debug, PartialEq, Clone)]
-pub enum KeyType {
-    None = 0x0,
-    String = 0x1,
-    SetStruct = 0x2,
-    SetStruct2 = 0x2f,
-    GUID = 0x3,
-    // Seen in ReadDialogues
-    SoftObjectProperty = 0x5,
-    SomeID = 0x6,
-    SomeID2 = 0x10,
-    EnumAgain = 0x4,
-    Enum = 0x7,
-    StructMaybe = 0xC,
-    ArrayStruct = 0xA, // Only used in ArrayProperty
-    Unknown = 0x2E,    // AcquiredItemBoxIds in Persistent.sav
-    Unknown2 = 0x33,   // CharacterPersistentDataList in Persistent.sav
+/// Works out the size of untagged struct keys or values with [find_struct_size], which needs the other side to have a fixed size.
+#[binrw::parser(reader)]
+fn read_struct_sizes(
+    size_in_bytes: u32,
+    num_keys_to_remove: u32,
+    key_type: &str,
+    value_type: &str,
+) -> BinResult<(Option<u32>, Option<u32>)> {
+    let key_size = fixed_sub_size_in_bytes(key_type);
+    let value_size = fixed_sub_size_in_bytes(value_type);
+
+    if key_type == "StructProperty"
+        && let Some(value_size) = value_size
+    {
+        let key_size =
+            find_struct_size(reader, size_in_bytes, num_keys_to_remove, None, value_size)?;
+        return Ok((key_size, None));
+    }
+    if value_type == "StructProperty"
+        && let Some(key_size) = key_size
+    {
+        let value_size = find_struct_size(
+            reader,
+            size_in_bytes,
+            num_keys_to_remove,
+            Some(key_size),
+            key_size,
+        )?;
+        return Ok((None, value_size));
+    }
+    Ok((None, None))
 }
 
 #[binrw::parser(reader, endian)]
 fn custom_parser(
+    start: u64,
     size_in_bytes: u32,
-    key_type: &KeyType,
+    count: u32,
+    key_type: &str,
     value_type: &str,
+    struct_sizes: (Option<u32>, Option<u32>),
 ) -> BinResult<Vec<MapEntry>> {
     let mut result = Vec::<MapEntry>::new();
 
-    let mut current = reader.stream_position()?;
-    let end = current + size_in_bytes as u64 - 5 - 3;
+    for i in 0..count {
+        let pos = reader.stream_position()?;
+        let entry = MapEntry::read_options(reader, endian, (key_type, value_type, struct_sizes));
+        result.push(in_property(entry, &format!("[{i}]"), pos)?);
+    }
 
-    while current < end {
-        result.push(MapEntry::read_options(
-            reader,
-            endian,
-            (key_type, value_type),
-        )?);
-        current = reader.stream_position()?;
+    let end = reader.stream_position()?;
+    if end - start != size_in_bytes as u64 {
+        return Err(Error::SizeMismatch {
+            pos: start,
+            expected_size: size_in_bytes as u64,
+            actual_size: end - start,
+        }
+        .into());
     }
     Ok(result)
 }
 
 fn calc_entry_size_in_bytes(prop: &MapProperty) -> u32 {
-    // TODO: complete guesswork still
-    let mut size = 4;
+    // 4 bytes for the number of keys to remove, and 4 bytes for the entry count
+    let mut size = 4 + 4;
 
+    for key in &prop.keys_to_remove {
+        size += calc_sub_size_in_bytes(key);
+    }
     for entry in &prop.entries {
-        size += match &entry.key {
-            MapKeyProperty::String(string_map_key) => {
-                crate::common::size_of_string_with_length(&string_map_key.value)
-            }
-            MapKeyProperty::StructMaybe(struct_maybe_key) => {
-                crate::common::size_of_string_with_length(&struct_maybe_key.unk_name)
-                    + 8
-                    + crate::common::size_of_string_with_length(&struct_maybe_key.unk_type)
-                    + crate::common::size_of_string_with_length(&struct_maybe_key.struct_name)
-                    + 17
-                    + crate::structure::calc_size_in_bytes(&struct_maybe_key.r#struct)
-            }
-            MapKeyProperty::Enum(map_sub_enum_property) => {
-                crate::common::size_of_string_with_length(&map_sub_enum_property.value)
-            }
-            MapKeyProperty::EnumAgain(map_sub_enum_property) => {
-                crate::common::size_of_string_with_length(&map_sub_enum_property.value)
-            }
-            MapKeyProperty::GUID(guid) => guid.size_in_bytes(),
-            MapKeyProperty::SomeID(guid) => guid.size_in_bytes(),
-            MapKeyProperty::SomeID2(guid) => guid.size_in_bytes(),
-            MapKeyProperty::ArrayStruct(struct_maybe_key) => {
-                crate::common::size_of_string_with_length(&struct_maybe_key.unk_name)
-                    + 8
-                    + crate::common::size_of_string_with_length(&struct_maybe_key.unk_type)
-                    + crate::common::size_of_string_with_length(&struct_maybe_key.struct_name)
-                    + 17
-                    + crate::structure::calc_size_in_bytes(&struct_maybe_key.r#struct)
-            }
-            MapKeyProperty::SomeID3(guid) => guid.size_in_bytes(),
-            MapKeyProperty::SoftObjectProperty(string_map_key) => {
-                crate::common::size_of_string_with_length(&string_map_key.value)
-            }
-        };
-        size += match &entry.value {
-            MabSubProperty::Name(map_sub_name_property) => {
-                crate::common::size_of_string_with_length(&map_sub_name_property.value)
-            }
-            MabSubProperty::Struct(map_sub_struct_property) => 0,
-            MabSubProperty::Float(map_sub_float_property) => 4,
-            MabSubProperty::String(map_sub_str_property) => {
-                crate::common::size_of_string_with_length(&map_sub_str_property.value)
-            }
-            MabSubProperty::Bool(map_sub_bool_property) => 1,
-            MabSubProperty::Int(map_sub_int_property) => 4,
-            MabSubProperty::Enum(map_sub_enum_property) => {
-                crate::common::size_of_string_with_length(&map_sub_enum_property.value)
-            }
-        }
+        size += calc_sub_size_in_bytes(&entry.key) + calc_sub_size_in_bytes(&entry.value);
     }
 
     size
@@ -275,11 +451,16 @@ fn calc_entry_size_in_bytes(prop: &MapProperty) -> u32 {
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UMapProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapProperty {
+    #[br(temp)]
     #[bw(calc = calc_entry_size_in_bytes(self))]
-    pub size_in_bytes: u32,
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
 
-    #[brw(pad_before = 4)]
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
     pub key_name: String,
@@ -288,11 +469,31 @@ pub struct MapProperty {
     #[bw(write_with = write_string_with_length)]
     pub value_name: String,
 
-    #[brw(pad_before = 5)]
-    #[br(dbg)]
-    pub map_key_type: KeyType,
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, None))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
+
+    #[br(temp, parse_with = current_position)]
+    #[bw(ignore)]
+    start: u64,
 
-    #[br(parse_with = custom_parser, args(size_in_bytes, &map_key_type, &value_name))]
+    #[br(temp)]
+    #[bw(calc = keys_to_remove.len() as u32)]
+    num_keys_to_remove: u32,
+
+    #[br(temp, parse_with = read_struct_sizes, args(size_in_bytes, num_keys_to_remove, &key_name, &value_name))]
+    #[bw(ignore)]
+    struct_sizes: (Option<u32>, Option<u32>),
+
+    /// Keys that were removed compared to the defaults, this is usually empty.
+    #[br(parse_with = read_sub_properties, args(num_keys_to_remove, &key_name, struct_sizes.0))]
+    pub keys_to_remove: Vec<MabSubProperty>,
+
+    #[br(temp)]
+    #[bw(calc = entries.len() as u32)]
+    num_entries: u32,
+
+    #[br(parse_with = custom_parser, args(start, size_in_bytes, num_entries, &key_name, &value_name, struct_sizes))]
     pub entries: Vec<MapEntry>,
 }
 
@@ -302,11 +503,9 @@ impl PropertyBase for MapProperty {
     }
 
     fn size_in_bytes(&self) -> u32 {
-        4 + 4
+        self.tag.tag_size_in_bytes()
             + crate::common::size_of_string_with_length(&self.key_name)
             + crate::common::size_of_string_with_length(&self.value_name)
-            + 5
-            + 4
             + calc_entry_size_in_bytes(self)
     }
 }
@@ -333,7 +532,7 @@ mod tests {
         let decoded = MapProperty::read_le(&mut cursor).unwrap();
         assert_eq!(decoded.key_name, "StrProperty");
         assert_eq!(decoded.value_name, "StrProperty");
-        let MapKeyProperty::String(key_property) = &decoded.entries.first().unwrap().key else {
+        let MabSubProperty::String(key_property) = &decoded.entries.first().unwrap().key else {
             panic!("StrProperty!")
         };
         let MabSubProperty::String(value_property) = &decoded.entries.first().unwrap().value else {
@@ -355,11 +554,12 @@ mod tests {
             0x41, 0x4d, 0x45, 0x20, 0x31, 0x00,
         ];
         let property = MapProperty {
+            tag: PropertyTag::default(),
             key_name: "StrProperty".to_string(),
             value_name: "StrProperty".to_string(),
-            map_key_type: KeyType::String,
+            keys_to_remove: Vec::new(),
             entries: vec![MapEntry {
-                key: MapKeyProperty::String(StringMapKey {
+                key: MabSubProperty::String(MapSubStrProperty {
                     value: "AR0XJGFWA6HNIQ1AAUJ9UR828".to_string(),
                 }),
                 value: MabSubProperty::String(MapSubStrProperty {
@@ -375,6 +575,55 @@ mod tests {
         }
 
         assert_eq!(expected_data, &buffer[..]);
+        assert_eq!(property.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn edited_map_size() {
+        let data = [
+            0x31, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x53, 0x74,
+            0x72, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x0c, 0x00, 0x00, 0x00,
+            0x53, 0x74, 0x72, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x1a, 0x00, 0x00, 0x00, 0x41, 0x52, 0x30,
+            0x58, 0x4a, 0x47, 0x46, 0x57, 0x41, 0x36, 0x48, 0x4e, 0x49, 0x51, 0x31, 0x41, 0x41,
+            0x55, 0x4a, 0x39, 0x55, 0x52, 0x38, 0x32, 0x38, 0x00, 0x07, 0x00, 0x00, 0x00, 0x4e,
+            0x41, 0x4d, 0x45, 0x20, 0x31, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let mut decoded = MapProperty::read_le(&mut cursor).unwrap();
+
+        // The size in the tag covers both counts and the entries, but not the key and value names
+        assert_eq!(
+            calc_entry_size_in_bytes(&decoded),
+            u32::from_le_bytes(data[0..4].try_into().unwrap())
+        );
+
+        decoded.entries.push(MapEntry {
+            key: MabSubProperty::String(MapSubStrProperty {
+                value: "ABC".to_string(),
+            }),
+            value: MabSubProperty::String(MapSubStrProperty {
+                value: "NAME 2".to_string(),
+            }),
+        });
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        // 4 + 4 bytes for the key, 4 + 7 bytes for the value
+        assert_eq!(buffer.len(), data.len() + 19);
+        assert_eq!(
+            u32::from_le_bytes(buffer[0..4].try_into().unwrap()),
+            0x31 + 19
+        );
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+
+        let mut cursor = Cursor::new(&buffer);
+        let decoded = MapProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.entries.len(), 2);
     }
 
     #[test]
@@ -403,7 +652,8 @@ mod tests {
         let decoded = MapProperty::read_le(&mut cursor).unwrap();
         assert_eq!(decoded.key_name, "NameProperty");
         assert_eq!(decoded.value_name, "IntProperty");
-        let MapKeyProperty::Enum(key_property) = &decoded.entries.first().unwrap().key else {
+        assert_eq!(decoded.entries.len(), 7);
+        let MabSubProperty::Name(key_property) = &decoded.entries.first().unwrap().key else {
             panic!("Name!")
         };
         let MabSubProperty::Int(value_property) = &decoded.entries.first().unwrap().value else {
@@ -412,5 +662,161 @@ mod tests {
         assert_eq!(key_property.value, "SelectedMachine");
         assert_eq!(value_property.value, 2);
         // TODO: test the rest of the values
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn guid_int_map() {
+        let data = [
+            0x1c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x53, 0x74,
+            0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x0c,
+            0x00, 0x00, 0x00, 0x49, 0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04,
+            0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x05, 0x00,
+            0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = MapProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.key_name, "StructProperty");
+        let MabSubProperty::Guid(key_property) = &decoded.entries.first().unwrap().key else {
+            panic!("Guid!")
+        };
+        assert_eq!(key_property.a, 0x04030201);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn vector_int_map() {
+        let data = [
+            0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x53, 0x74,
+            0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x0c,
+            0x00, 0x00, 0x00, 0x49, 0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f,
+            0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x40, 0x40, 0x05, 0x00, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = MapProperty::read_le(&mut cursor).unwrap();
+        // 12 bytes can't be a GUID, so the key is kept as-is
+        let MabSubProperty::RawStruct(key_property) = &decoded.entries.first().unwrap().key else {
+            panic!("RawStruct!")
+        };
+        assert_eq!(key_property.len(), 12);
+        let MabSubProperty::Int(value_property) = &decoded.entries.first().unwrap().value else {
+            panic!("Int!")
+        };
+        assert_eq!(value_property.value, 5);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn byte_int_map() {
+        // Plain bytes, where the first key and value look like the length of a name
+        let data = [
+            0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x42, 0x79,
+            0x74, 0x65, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x0c, 0x00, 0x00,
+            0x00, 0x49, 0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x03,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = MapProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.entries.len(), 2);
+        let MabSubProperty::Byte(key_property) = &decoded.entries[1].key else {
+            panic!("Byte!")
+        };
+        assert_eq!(key_property.value, ByteValue::Byte(3));
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+
+    #[test]
+    fn enum_int_map() {
+        let data = [
+            0x1c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x42, 0x79,
+            0x74, 0x65, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x0c, 0x00, 0x00,
+            0x00, 0x49, 0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x45, 0x44,
+            0x41, 0x53, 0x74, 0x61, 0x74, 0x65, 0x3a, 0x3a, 0x41, 0x00, 0x07, 0x00, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = MapProperty::read_le(&mut cursor).unwrap();
+        let MabSubProperty::Byte(key_property) = &decoded.entries[0].key else {
+            panic!("Byte!")
+        };
+        assert_eq!(
+            key_property.value,
+            ByteValue::Enum("EDAState::A".to_string())
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+
+    #[test]
+    fn soft_object_bool_map() {
+        // Like ReadDialogues in LocalProfile.sav
+        let data = [
+            0x2e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x53, 0x6f,
+            0x66, 0x74, 0x4f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72,
+            0x74, 0x79, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x42, 0x6f, 0x6f, 0x6c, 0x50, 0x72, 0x6f,
+            0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+            0x00, 0x1d, 0x00, 0x00, 0x00, 0x2f, 0x47, 0x61, 0x6d, 0x65, 0x2f, 0x44, 0x69, 0x61,
+            0x6c, 0x6f, 0x67, 0x75, 0x65, 0x2f, 0x44, 0x41, 0x5f, 0x46, 0x6f, 0x6f, 0x2e, 0x44,
+            0x41, 0x5f, 0x46, 0x6f, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = MapProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.key_name, "SoftObjectProperty");
+        let MabSubProperty::SoftObject(key_property) = &decoded.entries.first().unwrap().key else {
+            panic!("SoftObjectPath!")
+        };
+        assert_eq!(
+            key_property.asset_path.asset_name.as_deref(),
+            Some("DA_Foo")
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
     }
 }
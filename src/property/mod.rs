@@ -1,5 +1,8 @@
 pub mod array_property;
 
+mod byte_property;
+pub use self::byte_property::{ByteProperty, ByteValue, EnumValue};
+
 mod bool_property;
 pub use self::bool_property::BoolProperty;
 
@@ -11,20 +14,42 @@ pub use float_property::FloatProperty;
 
 mod generic_property;
 pub use self::generic_property::GenericProperty;
+pub(crate) use self::generic_property::{
+    looks_like_name, looks_like_properties, read_properties_until_none, write_properties_until_none,
+};
 
 mod int_property;
 pub use self::int_property::IntProperty;
 
 pub mod map_property;
 
+mod numeric_property;
+pub use self::numeric_property::{
+    DoubleProperty, Int8Property, Int16Property, Int64Property, UInt16Property, UInt32Property,
+    UInt64Property,
+};
+
 mod name_property;
 pub use self::name_property::NameProperty;
 
+mod property_tag;
+pub use self::property_tag::PropertyTag;
+pub(crate) use self::property_tag::{read_tag_end, write_tag_end};
+
 pub mod set_property;
 
+mod object_property;
+pub use self::object_property::ObjectProperty;
+
+mod soft_object_property;
+pub use self::soft_object_property::SoftObjectProperty;
+
 mod str_property;
 pub use self::str_property::StrProperty;
 
+mod text_property;
+pub use self::text_property::{Text, TextHistory, TextProperty};
+
 mod struct_property;
 pub use self::struct_property::StructProperty;
 
@@ -0,0 +1,142 @@
+use binrw::binrw;
+
+use super::{PropertyBase, PropertyTag};
+
+/// These only differ in the type of their value, see [IntProperty](super::IntProperty) for what they look like.
+macro_rules! numeric_property {
+    ($(#[$attr:meta])* $name:ident, $type:ty) => {
+        $(#[$attr])*
+        #[binrw]
+        #[derive(Debug)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct $name {
+            #[bw(args(size_of::<$type>() as u32))]
+            pub tag: PropertyTag,
+            pub value: $type,
+        }
+
+        impl PropertyBase for $name {
+            fn type_name() -> &'static str {
+                stringify!($name)
+            }
+
+            fn size_in_bytes(&self) -> u32 {
+                self.tag.tag_size_in_bytes() + size_of::<$type>() as u32
+            }
+        }
+    };
+}
+
+numeric_property!(
+    /// A signed 8-bit integer.
+    ///
+    /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FInt8Property?application_version=4.27).
+    Int8Property,
+    i8
+);
+
+numeric_property!(
+    /// A signed 16-bit integer.
+    ///
+    /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FInt16Property?application_version=4.27).
+    Int16Property,
+    i16
+);
+
+numeric_property!(
+    /// A signed 64-bit integer.
+    ///
+    /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FInt64Property?application_version=4.27).
+    Int64Property,
+    i64
+);
+
+numeric_property!(
+    /// An unsigned 16-bit integer.
+    ///
+    /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FUInt16Property?application_version=4.27).
+    UInt16Property,
+    u16
+);
+
+numeric_property!(
+    /// An unsigned 32-bit integer.
+    ///
+    /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FUInt32Property?application_version=4.27).
+    UInt32Property,
+    u32
+);
+
+numeric_property!(
+    /// An unsigned 64-bit integer.
+    ///
+    /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FUInt64Property?application_version=4.27).
+    UInt64Property,
+    u64
+);
+
+numeric_property!(
+    /// A double.
+    ///
+    /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FDoubleProperty?application_version=4.27).
+    DoubleProperty,
+    f64
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::{BinRead, BinWrite};
+    use std::io::Cursor;
+
+    #[test]
+    fn roundtrip_int64() {
+        let data = [
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xfe, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = Int64Property::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.value, -2);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+
+    #[test]
+    fn write_double() {
+        let expected_data: [u8; 17] = [
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0xf8, 0x3f,
+        ];
+        let property = DoubleProperty {
+            tag: PropertyTag::default(),
+            value: 1.5,
+        };
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            property.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(expected_data, &buffer[..]);
+        assert_eq!(DoubleProperty::type_name(), "DoubleProperty");
+    }
+
+    #[test]
+    fn read_uint16() {
+        let data = [
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = UInt16Property::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.value, 0x1234);
+    }
+}
@@ -0,0 +1,57 @@
+use binrw::binrw;
+
+use crate::structure::ObjectPath;
+
+use super::{PropertyBase, PropertyTag};
+
+/// A reference to an object, which is saved as its path.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UObjectProperty?application_version=4.27).
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjectProperty {
+    #[bw(args(value.size_in_bytes()))]
+    pub tag: PropertyTag,
+    pub value: ObjectPath,
+}
+
+impl PropertyBase for ObjectProperty {
+    fn type_name() -> &'static str {
+        "ObjectProperty"
+    }
+
+    fn size_in_bytes(&self) -> u32 {
+        self.tag.tag_size_in_bytes() + self.value.size_in_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::{BinRead, BinWrite};
+    use std::io::Cursor;
+
+    #[test]
+    fn roundtrip_object() {
+        let data = [
+            0x1e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1a, 0x00, 0x00, 0x00, 0x2f,
+            0x47, 0x61, 0x6d, 0x65, 0x2f, 0x4d, 0x61, 0x70, 0x2e, 0x4d, 0x61, 0x70, 0x3a, 0x4c,
+            0x65, 0x76, 0x65, 0x6c, 0x2e, 0x41, 0x63, 0x74, 0x6f, 0x72, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ObjectProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.value.package_name, "/Game/Map");
+        assert_eq!(decoded.value.asset_name.as_deref(), Some("Map"));
+        assert_eq!(decoded.value.sub_path.as_deref(), Some("Level.Actor"));
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+}
@@ -0,0 +1,147 @@
+use binrw::{BinRead, BinResult, BinWrite, Endian};
+use std::io::{Read, Seek, Write};
+
+use crate::{common::check_padding, structure::Guid};
+
+/// The header in front of every property's value.
+///
+/// Some property types have extra data in the middle of their tag, like the struct name of a [StructProperty](super::StructProperty). That data lives in the property itself instead.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FPropertyTag?application_version=4.27).
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PropertyTag {
+    /// Size of the value in bytes. This is calculated again when writing.
+    pub size_in_bytes: u32,
+    /// Index of the element, if this property is part of a static array.
+    pub array_index: u32,
+    /// Only [StructProperty](super::StructProperty) has this.
+    pub struct_guid: Option<Guid>,
+    pub property_guid: Option<Guid>,
+}
+
+impl PropertyTag {
+    /// Size of the tag in bytes, not including any extra data from the property type.
+    pub(crate) fn tag_size_in_bytes(&self) -> u32 {
+        let mut size = 4 + 4 + 1;
+        if self.struct_guid.is_some() {
+            size += 16;
+        }
+        if self.property_guid.is_some() {
+            size += 16;
+        }
+        size
+    }
+}
+
+// This is only for property types that don't have anything extra in their tag, the others put it together themselves.
+impl BinRead for PropertyTag {
+    type Args<'a> = ();
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        _: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        Ok(Self {
+            size_in_bytes: u32::read_options(reader, endian, ())?,
+            array_index: u32::read_options(reader, endian, ())?,
+            struct_guid: None,
+            property_guid: read_property_guid(reader, endian, ())?,
+        })
+    }
+}
+
+impl BinWrite for PropertyTag {
+    /// The size of the value.
+    type Args<'a> = (u32,);
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (size_in_bytes,): Self::Args<'_>,
+    ) -> BinResult<()> {
+        size_in_bytes.write_options(writer, endian, ())?;
+        self.array_index.write_options(writer, endian, ())?;
+        write_property_guid(&self.property_guid, writer, endian, ())
+    }
+}
+
+/// Reads the rest of a tag after the data in the middle of it, for property types that have some.
+#[binrw::parser(reader, endian)]
+pub(crate) fn read_tag_end(
+    size_in_bytes: u32,
+    array_index: u32,
+    struct_guid: Option<Guid>,
+) -> BinResult<PropertyTag> {
+    if let Some(struct_guid) = struct_guid {
+        // The GUID was read right before this
+        let pos = reader.stream_position()? - 16;
+        check_padding(pos, struct_guid == Guid::default())?;
+    }
+    Ok(PropertyTag {
+        size_in_bytes,
+        array_index,
+        struct_guid,
+        property_guid: read_property_guid(reader, endian, ())?,
+    })
+}
+
+/// Writes the rest of a tag, the counterpart to [read_tag_end].
+#[binrw::writer(writer, endian)]
+pub(crate) fn write_tag_end(tag: &PropertyTag) -> BinResult<()> {
+    write_property_guid(&tag.property_guid, writer, endian, ())
+}
+
+/// Reads the GUID at the end of a tag, which is only there if the byte before it is set.
+#[binrw::parser(reader, endian)]
+fn read_property_guid() -> BinResult<Option<Guid>> {
+    let pos = reader.stream_position()?;
+    let has_guid = u8::read_options(reader, endian, ())?;
+    check_padding(pos, has_guid == 0)?;
+    if has_guid != 0 {
+        Ok(Some(Guid::read_options(reader, endian, ())?))
+    } else {
+        Ok(None)
+    }
+}
+
+#[binrw::writer(writer, endian)]
+fn write_property_guid(guid: &Option<Guid>) -> BinResult<()> {
+    match guid {
+        Some(guid) => {
+            1u8.write_options(writer, endian, ())?;
+            guid.write_options(writer, endian, ())
+        }
+        None => 0u8.write_options(writer, endian, ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn roundtrip_property_guid() {
+        let data = [
+            0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x01, 0x02, 0x03, 0x04, 0x05,
+            0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = PropertyTag::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.size_in_bytes, 4);
+        assert_eq!(decoded.array_index, 2);
+        assert_eq!(decoded.property_guid.unwrap().a, 0x04030201);
+        assert_eq!(decoded.tag_size_in_bytes(), data.len() as u32);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le_args(&mut cursor, (4,)).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+}
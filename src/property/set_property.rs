@@ -1,114 +1,211 @@
-use crate::common::{read_string_with_length, write_string_with_length};
+use crate::common::{current_position, read_string_with_length, write_string_with_length};
+use crate::structure::Guid;
+use crate::{Error, error::in_property};
 use binrw::{BinRead, BinResult, binrw};
 
 use super::{
-    GenericProperty, PropertyBase,
-    map_property::{KeyType, MapSubStrProperty},
-};
-
-// hack, we should be checking for "none" instead
-#[binrw::parser(reader, endian)]
-fn cc() -> BinResult<Vec<GenericProperty>> {
-    let mut result = Vec::<GenericProperty>::new();
-
-    loop {
-        if let Ok(str) = GenericProperty::read_options(reader, endian, ()) {
-            if str.property_name == "None" {
-                break;
-            }
-            result.push(str);
-        } else {
-            break;
-        }
-    }
-    Ok(result)
-}
-
-#[binrw]
-#[derive(Debug)]
-#[br(import { magic: &str, key_type: &KeyType })]
-pub enum SetValue {
-    #[br(pre_assert("StructProperty" == magic && *key_type != KeyType::Unknown && *key_type != KeyType::EnumAgain))]
-    Struct {
-        #[br(parse_with = cc)]
-        fields: Vec<GenericProperty>,
+    PropertyBase, PropertyTag,
+    map_property::{
+        MabSubProperty, MapSubNameProperty, calc_sub_size_in_bytes, find_struct_size,
+        read_sub_properties, read_sub_property,
     },
-    #[br(pre_assert("StringProperty" == magic || "NameProperty" == magic))]
-    String(MapSubStrProperty),
-    #[br(pre_assert("StructProperty" == magic && *key_type == KeyType::Unknown))]
-    Unknown { unk: [u8; 736] },
-    #[br(pre_assert("StructProperty" == magic && *key_type == KeyType::EnumAgain))]
-    Unknown2 { unk: [u8; 64] },
-}
+    property_tag::{read_tag_end, write_tag_end},
+};
 
 #[binrw]
 #[derive(Debug)]
-#[br(import(value_type: &str, key_type: &KeyType))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import(value_type: &str, struct_size: Option<u32>))]
 pub struct SetEntry {
-    #[br(args { magic: value_type, key_type })]
-    #[br(dbg)]
-    pub key: SetValue,
+    #[br(parse_with = read_sub_property, args(value_type, struct_size))]
+    pub key: MabSubProperty,
 }
 
 #[binrw::parser(reader, endian)]
 fn custom_parser(
+    start: u64,
     size_in_bytes: u32,
+    count: u32,
     value_type: &str,
-    key_type: &KeyType,
+    struct_size: Option<u32>,
 ) -> BinResult<Vec<SetEntry>> {
     let mut result = Vec::<SetEntry>::new();
 
-    let mut current = reader.stream_position()?;
-    let end = current + size_in_bytes as u64 - 8;
+    for i in 0..count {
+        let pos = reader.stream_position()?;
+        let entry = SetEntry::read_options(reader, endian, (value_type, struct_size));
+        result.push(in_property(entry, &format!("[{i}]"), pos)?);
+    }
 
-    while current < end {
-        result.push(SetEntry::read_options(
-            reader,
-            endian,
-            (value_type, key_type),
-        )?);
-        current = reader.stream_position()?;
+    let end = reader.stream_position()?;
+    if end - start != size_in_bytes as u64 {
+        return Err(Error::SizeMismatch {
+            pos: start,
+            expected_size: size_in_bytes as u64,
+            actual_size: end - start,
+        }
+        .into());
     }
     Ok(result)
 }
 
+/// Works out the size of untagged struct elements with [find_struct_size].
+#[binrw::parser(reader)]
+fn read_struct_size(
+    size_in_bytes: u32,
+    num_elements_to_remove: u32,
+    value_type: &str,
+) -> BinResult<Option<u32>> {
+    if value_type != "StructProperty" {
+        return Ok(None);
+    }
+    find_struct_size(reader, size_in_bytes, num_elements_to_remove, None, 0)
+}
+
+fn calc_entry_size_in_bytes(prop: &SetProperty) -> u32 {
+    // 4 bytes for the number of elements to remove, and 4 bytes for the entry count
+    let mut size = 4 + 4;
+
+    for element in &prop.elements_to_remove {
+        size += calc_sub_size_in_bytes(element);
+    }
+    for entry in &prop.entries {
+        size += calc_sub_size_in_bytes(&entry.key);
+    }
+
+    size
+}
+
 /// A set.
 ///
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/USetProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetProperty {
-    pub size_in_bytes: u32,
+    #[br(temp)]
+    #[bw(calc = calc_entry_size_in_bytes(self))]
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
 
-    #[brw(pad_before = 4)]
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
-    #[br(dbg)]
     pub key_name: String,
 
-    #[brw(pad_before = 5)]
-    #[br(dbg)]
-    pub key_type: KeyType,
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, None))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
+
+    #[br(temp, parse_with = current_position)]
+    #[bw(ignore)]
+    start: u64,
+
+    #[br(temp)]
+    #[bw(calc = elements_to_remove.len() as u32)]
+    num_elements_to_remove: u32,
 
-    #[br(parse_with = custom_parser, args(size_in_bytes, &key_name, &key_type))]
+    #[br(temp, parse_with = read_struct_size, args(size_in_bytes, num_elements_to_remove, &key_name))]
+    #[bw(ignore)]
+    struct_size: Option<u32>,
+
+    /// Elements that were removed compared to the defaults, this is usually empty.
+    #[br(parse_with = read_sub_properties, args(num_elements_to_remove, &key_name, struct_size))]
+    pub elements_to_remove: Vec<MabSubProperty>,
+
+    #[br(temp)]
+    #[bw(calc = entries.len() as u32)]
+    num_entries: u32,
+
+    #[br(parse_with = custom_parser, args(start, size_in_bytes, num_entries, &key_name, struct_size))]
     pub entries: Vec<SetEntry>,
 }
 
+impl SetProperty {
+    /// The elements that are GUIDs, like the ids in `AcquiredItemBoxIds` or `OpenedStrongBoxIds`.
+    pub fn guids(&self) -> impl Iterator<Item = &Guid> {
+        self.entries.iter().filter_map(|entry| match &entry.key {
+            MabSubProperty::Guid(guid) => Some(guid),
+            _ => None,
+        })
+    }
+
+    /// Whether `guid` is in the set.
+    pub fn contains_guid(&self, guid: &Guid) -> bool {
+        self.guids().any(|x| x == guid)
+    }
+
+    /// Adds `guid` to the set, returning false if it was already there.
+    pub fn insert_guid(&mut self, guid: Guid) -> bool {
+        if self.contains_guid(&guid) {
+            return false;
+        }
+        self.entries.push(SetEntry {
+            key: MabSubProperty::Guid(guid),
+        });
+        true
+    }
+
+    /// Removes `guid` from the set, returning false if it wasn't there.
+    pub fn remove_guid(&mut self, guid: &Guid) -> bool {
+        let len = self.entries.len();
+        self.entries
+            .retain(|entry| !matches!(&entry.key, MabSubProperty::Guid(x) if x == guid));
+        self.entries.len() != len
+    }
+
+    /// The elements that are names, like the tags in `ReachedDistricts` or `ReleasedCheckpoints`.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter_map(|entry| match &entry.key {
+            MabSubProperty::Name(name) => Some(name.value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether `name` is in the set.
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.names().any(|x| x == name)
+    }
+
+    /// Adds `name` to the set, returning false if it was already there.
+    pub fn insert_name(&mut self, name: impl Into<String>) -> bool {
+        let name = name.into();
+        if self.contains_name(&name) {
+            return false;
+        }
+        self.entries.push(SetEntry {
+            key: MabSubProperty::Name(MapSubNameProperty { value: name }),
+        });
+        true
+    }
+
+    /// Removes `name` from the set, returning false if it wasn't there.
+    pub fn remove_name(&mut self, name: &str) -> bool {
+        let len = self.entries.len();
+        self.entries
+            .retain(|entry| !matches!(&entry.key, MabSubProperty::Name(x) if x.value == name));
+        self.entries.len() != len
+    }
+}
+
 impl PropertyBase for SetProperty {
     fn type_name() -> &'static str {
         "SetProperty"
     }
 
     fn size_in_bytes(&self) -> u32 {
-        // todo
-        0
+        self.tag.tag_size_in_bytes()
+            + crate::common::size_of_string_with_length(&self.key_name)
+            + calc_entry_size_in_bytes(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use binrw::BinRead;
+    use binrw::{BinRead, BinWrite};
     use std::io::Cursor;
 
     #[test]
@@ -155,7 +252,156 @@ mod tests {
         let mut cursor = Cursor::new(data);
         let decoded = SetProperty::read_le(&mut cursor).unwrap();
         assert_eq!(decoded.key_name, "StructProperty");
+        assert_eq!(decoded.entries.len(), 2);
+        let MabSubProperty::Struct(value) = &decoded.entries[0].key else {
+            panic!("StructProperty!")
+        };
+        assert_eq!(value.fields.len(), 2);
+        assert_eq!(value.fields[0].property_name, "PrimaryAssetType");
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        // The fixture has the "None" of the struct this set is in at the end
+        assert_eq!(&data[..data.len() - 9], &buffer[..]);
+
+        // Taking out an element should update the count and size
+        let mut edited = decoded;
+        edited.entries.pop();
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            edited.write_le(&mut cursor).unwrap();
+        }
+
+        let mut cursor = Cursor::new(&buffer);
+        let decoded = SetProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.entries.len(), 1);
+        assert_eq!(cursor.position(), buffer.len() as u64);
+        assert_eq!(buffer[..4], [0xe7, 0x00, 0x00, 0x00]);
     }
-}
 
-// TODO: write test
+    #[test]
+    fn write_name_set() {
+        let expected_data = [
+            0x38, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x4e, 0x61,
+            0x6d, 0x65, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x44, 0x69, 0x73, 0x74,
+            0x72, 0x69, 0x63, 0x74, 0x5f, 0x4d, 0x30, 0x31, 0x41, 0x30, 0x31, 0x5f, 0x30, 0x30,
+            0x31, 0x00, 0x14, 0x00, 0x00, 0x00, 0x44, 0x69, 0x73, 0x74, 0x72, 0x69, 0x63, 0x74,
+            0x5f, 0x4d, 0x30, 0x31, 0x41, 0x30, 0x31, 0x5f, 0x30, 0x30, 0x32, 0x00,
+        ];
+        let property = SetProperty {
+            tag: PropertyTag::default(),
+            key_name: "NameProperty".to_string(),
+            elements_to_remove: Vec::new(),
+            entries: vec![
+                SetEntry {
+                    key: MabSubProperty::Name(MapSubNameProperty {
+                        value: "District_M01A01_001".to_string(),
+                    }),
+                },
+                SetEntry {
+                    key: MabSubProperty::Name(MapSubNameProperty {
+                        value: "District_M01A01_002".to_string(),
+                    }),
+                },
+            ],
+        };
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            property.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(expected_data, &buffer[..]);
+        assert_eq!(property.size_in_bytes(), buffer.len() as u32);
+
+        let mut cursor = Cursor::new(&buffer);
+        let mut decoded = SetProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.entries.len(), 2);
+        assert!(decoded.contains_name("District_M01A01_002"));
+        assert!(!decoded.insert_name("District_M01A01_001"));
+        assert!(decoded.remove_name("District_M01A01_001"));
+        assert!(!decoded.remove_name("District_M01A01_001"));
+        assert!(decoded.insert_name("District_M01A01_003"));
+        assert_eq!(
+            decoded.names().collect::<Vec<_>>(),
+            ["District_M01A01_002", "District_M01A01_003"]
+        );
+    }
+
+    #[test]
+    fn guid_set() {
+        // Like OpenedStrongBoxIds in Persistent.sav
+        let data = [
+            0x48, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x53, 0x74,
+            0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15,
+            0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20, 0x21, 0x22, 0x23,
+            0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f, 0x30, 0x31,
+            0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x3b, 0x3c, 0x3d, 0x3e, 0x3f,
+            0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x4b, 0x4c, 0x4d,
+            0x4e, 0x4f,
+        ];
+        let mut cursor = Cursor::new(data);
+        let mut decoded = SetProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.guids().count(), 4);
+
+        let first = *decoded.guids().next().unwrap();
+        assert_eq!(first.a, 0x13121110);
+        assert!(decoded.contains_guid(&first));
+        assert!(!decoded.insert_guid(first));
+
+        assert!(decoded.remove_guid(&first));
+        assert!(!decoded.contains_guid(&first));
+        assert_eq!(decoded.guids().count(), 3);
+        assert!(decoded.insert_guid(first));
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        // The first one is now at the end
+        assert_eq!(data[..36], buffer[..36]);
+        assert_eq!(data[52..], buffer[36..84]);
+        assert_eq!(data[36..52], buffer[84..]);
+    }
+
+    #[test]
+    fn vector_set() {
+        let data = [
+            0x2c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x53, 0x74,
+            0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0xe0, 0x40, 0x00, 0x00, 0x00, 0x41, 0x00, 0x00,
+            0x10, 0x41, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x00, 0x40,
+            0x00, 0x00, 0x40, 0x40, 0x00, 0x00, 0x80, 0x40, 0x00, 0x00, 0xa0, 0x40, 0x00, 0x00,
+            0xc0, 0x40,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = SetProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.elements_to_remove.len(), 1);
+        assert_eq!(decoded.entries.len(), 2);
+        // These are vectors, which aren't the size of a GUID
+        let MabSubProperty::RawStruct(value) = &decoded.entries[1].key else {
+            panic!("RawStruct!")
+        };
+        assert_eq!(value[..4], 4.0f32.to_le_bytes());
+        assert_eq!(decoded.guids().count(), 0);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+}
@@ -0,0 +1,27 @@
+use binrw::binrw;
+
+use crate::structure::SoftObjectPath;
+
+use super::{PropertyBase, PropertyTag};
+
+/// A reference to an asset that may not be loaded.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/USoftObjectProperty?application_version=4.27).
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftObjectProperty {
+    #[bw(args(value.size_in_bytes()))]
+    pub tag: PropertyTag,
+    pub value: SoftObjectPath,
+}
+
+impl PropertyBase for SoftObjectProperty {
+    fn type_name() -> &'static str {
+        "SoftObjectProperty"
+    }
+
+    fn size_in_bytes(&self) -> u32 {
+        self.tag.tag_size_in_bytes() + self.value.size_in_bytes()
+    }
+}
@@ -1,6 +1,11 @@
 use crate::{
     common::{read_string_with_length, write_string_with_length},
-    structure::Struct,
+    structure::{Guid, Struct, calc_size_in_bytes, read_struct},
+};
+
+use super::{
+    PropertyTag,
+    property_tag::{read_tag_end, write_tag_end},
 };
 use binrw::binrw;
 
@@ -9,13 +14,128 @@ use binrw::binrw;
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UStructProperty?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StructProperty {
-    pub unk: u32,
-    #[brw(pad_before = 4)]
+    #[br(temp)]
+    #[bw(calc = calc_size_in_bytes(r#struct))]
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
+
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
     pub struct_name: String,
-    #[br(args { magic: &struct_name })]
-    #[brw(pad_before = 17)]
+
+    #[br(temp)]
+    #[bw(calc = tag.struct_guid.unwrap_or_default())]
+    struct_guid: Guid,
+
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, Some(struct_guid)))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
+
+    #[br(parse_with = read_struct, args(&struct_name, Some(size_in_bytes)))]
     pub r#struct: Struct,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::{BinRead, BinWrite};
+    use std::io::Cursor;
+
+    #[test]
+    fn roundtrip_unknown_struct() {
+        let data = [
+            0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4d, 0x79,
+            0x53, 0x74, 0x72, 0x75, 0x63, 0x74, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00,
+            0x56, 0x61, 0x6c, 0x75, 0x65, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x49, 0x6e, 0x74, 0x50,
+            0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x4e, 0x6f, 0x6e,
+            0x65, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = StructProperty::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.struct_name, "MyStruct");
+        let Struct::Unknown {
+            struct_name,
+            properties,
+        } = &decoded.r#struct
+        else {
+            panic!("Expected an unknown struct, got {:?}", decoded.r#struct);
+        };
+        assert_eq!(struct_name, "MyStruct");
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties[0].property_name, "Value");
+        assert_eq!(
+            calc_size_in_bytes(&decoded.r#struct),
+            decoded.tag.size_in_bytes
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+
+    #[test]
+    fn roundtrip_unknown_native_struct() {
+        let data = [
+            0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x42, 0x6f,
+            0x78, 0x32, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x00,
+            0x40, 0x00, 0x00, 0x40, 0x40, 0x00, 0x00, 0x80, 0x40, 0x01,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = StructProperty::read_le(&mut cursor).unwrap();
+        let Struct::UnknownRaw { struct_name, raw } = &decoded.r#struct else {
+            panic!(
+                "Expected an unknown native struct, got {:?}",
+                decoded.r#struct
+            );
+        };
+        assert_eq!(struct_name, "Box2D");
+        assert_eq!(raw.len(), 17);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+
+    #[test]
+    fn roundtrip_vector_struct() {
+        let data = [
+            0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x56, 0x65,
+            0x63, 0x74, 0x6f, 0x72, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00,
+            0x00, 0x40, 0x00, 0x00, 0x40, 0x40,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = StructProperty::read_le(&mut cursor).unwrap();
+        let Struct::Vector(vector) = &decoded.r#struct else {
+            panic!("Expected a vector, got {:?}", decoded.r#struct);
+        };
+        assert_eq!(vector.x, 1.0);
+        assert_eq!(vector.z, 3.0);
+        // A vector is three floats, writing any other size corrupts everything after it
+        assert_eq!(calc_size_in_bytes(&decoded.r#struct), 12);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+}
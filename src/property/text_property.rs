@@ -0,0 +1,330 @@
+use binrw::{BinRead, BinResult, BinWrite, Endian, binrw};
+use std::io::SeekFrom;
+
+use crate::Error;
+use crate::common::{
+    read_bool_from, read_string_with_length, size_of_string_with_length, write_bool_as,
+    write_string_with_length,
+};
+
+use super::{PropertyBase, PropertyTag};
+
+/// Where the string of a [Text] comes from.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/Core/Internationalization/ETextHistoryType?application_version=4.27).
+#[binrw]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import { size_in_bytes: u32, start: u64 })]
+pub enum TextHistory {
+    /// Text that isn't localized, which may not have a string at all.
+    #[brw(magic = 0xffu8)]
+    None {
+        #[br(parse_with = read_culture_invariant_string)]
+        #[bw(write_with = write_culture_invariant_string)]
+        culture_invariant_string: Option<String>,
+    },
+    /// Text that's looked up by its namespace and key, falling back to the source string.
+    #[brw(magic = 0u8)]
+    Base {
+        #[br(parse_with = read_string_with_length)]
+        #[bw(write_with = write_string_with_length)]
+        namespace: String,
+
+        #[br(parse_with = read_string_with_length)]
+        #[bw(write_with = write_string_with_length)]
+        key: String,
+
+        #[br(parse_with = read_string_with_length)]
+        #[bw(write_with = write_string_with_length)]
+        source_string: String,
+    },
+    /// Text that comes from a string table.
+    #[brw(magic = 11u8)]
+    StringTableEntry {
+        #[br(parse_with = read_string_with_length)]
+        #[bw(write_with = write_string_with_length)]
+        table_id: String,
+
+        #[br(parse_with = read_string_with_length)]
+        #[bw(write_with = write_string_with_length)]
+        key: String,
+    },
+    /// A history type we don't know how to read (like formatted or transformed text), kept as-is so it's written back unchanged.
+    Unknown {
+        history_type: u8,
+
+        /// Everything after the history type, the size comes from the tag since these can't be read without understanding them.
+        #[br(parse_with = read_raw_history, args(size_in_bytes, start))]
+        raw: Vec<u8>,
+    },
+}
+
+/// Reads a bool saying whether there's a string, and then the string if there is one.
+#[binrw::parser(reader)]
+fn read_culture_invariant_string() -> BinResult<Option<String>> {
+    let has_string = read_bool_from(u32::read_le(reader)?);
+    if has_string {
+        Ok(Some(read_string_with_length(reader, Endian::Little, ())?))
+    } else {
+        Ok(None)
+    }
+}
+
+#[binrw::writer(writer)]
+fn write_culture_invariant_string(string: &Option<String>) -> BinResult<()> {
+    write_bool_as::<u32>(&string.is_some()).write_le(writer)?;
+    if let Some(string) = string {
+        write_string_with_length(string, writer, Endian::Little, ())?;
+    }
+    Ok(())
+}
+
+/// Reads the rest of a [Text] that starts at `start` and is `size_in_bytes` long.
+#[binrw::parser(reader)]
+fn read_raw_history(size_in_bytes: u32, start: u64) -> BinResult<Vec<u8>> {
+    let pos = reader.stream_position()?;
+    let used = pos - start;
+    let Some(size) = (size_in_bytes as u64).checked_sub(used) else {
+        return Err(Error::SizeMismatch {
+            pos: start,
+            expected_size: size_in_bytes as u64,
+            actual_size: used,
+        }
+        .into());
+    };
+
+    // The size comes straight from the file, so make sure there's that much data left before trusting it
+    let end = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(pos))?;
+    let remaining = end.saturating_sub(pos);
+    if size > remaining {
+        return Err(Error::Read {
+            pos,
+            message: format!("text history needs {size} more bytes, but only {remaining} are left"),
+        }
+        .into());
+    }
+
+    let mut raw = vec![0u8; size as usize];
+    reader.read_exact(&mut raw)?;
+    Ok(raw)
+}
+
+/// Where a [Text] starts, so [read_raw_history] knows how much of its size was already read.
+#[binrw::parser(reader)]
+fn read_stream_position() -> BinResult<u64> {
+    Ok(reader.stream_position()?)
+}
+
+impl TextHistory {
+    fn size_in_bytes(&self) -> u32 {
+        // All of them start with the history type
+        1 + match self {
+            TextHistory::None {
+                culture_invariant_string,
+            } => {
+                4 + culture_invariant_string
+                    .as_deref()
+                    .map_or(0, size_of_string_with_length)
+            }
+            TextHistory::Base {
+                namespace,
+                key,
+                source_string,
+            } => {
+                size_of_string_with_length(namespace)
+                    + size_of_string_with_length(key)
+                    + size_of_string_with_length(source_string)
+            }
+            TextHistory::StringTableEntry { table_id, key } => {
+                size_of_string_with_length(table_id) + size_of_string_with_length(key)
+            }
+            TextHistory::Unknown { raw, .. } => raw.len() as u32,
+        }
+    }
+}
+
+/// Localized text, as shown in the UI.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/Core/Internationalization/FText?application_version=4.27).
+#[binrw]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import { size_in_bytes: u32 })]
+pub struct Text {
+    #[br(temp, parse_with = read_stream_position)]
+    #[bw(ignore)]
+    start: u64,
+
+    pub flags: u32,
+
+    #[br(args { size_in_bytes, start })]
+    pub history: TextHistory,
+}
+
+impl Text {
+    fn size_in_bytes(&self) -> u32 {
+        4 + self.history.size_in_bytes()
+    }
+
+    /// The string to show, if there is one without looking anything up.
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.history {
+            TextHistory::None {
+                culture_invariant_string,
+            } => culture_invariant_string.as_deref(),
+            TextHistory::Base { source_string, .. } => Some(source_string),
+            _ => None,
+        }
+    }
+}
+
+/// A text property.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/UTextProperty?application_version=4.27).
+#[binrw]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextProperty {
+    #[bw(args(value.size_in_bytes()))]
+    pub tag: PropertyTag,
+
+    #[br(args { size_in_bytes: tag.size_in_bytes })]
+    pub value: Text,
+}
+
+impl PropertyBase for TextProperty {
+    fn type_name() -> &'static str {
+        "TextProperty"
+    }
+
+    fn size_in_bytes(&self) -> u32 {
+        self.tag.tag_size_in_bytes() + self.value.size_in_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::in_property;
+    use binrw::{BinRead, BinWrite};
+    use std::io::Cursor;
+
+    fn roundtrip(data: &[u8]) -> TextProperty {
+        let mut cursor = Cursor::new(data);
+        let decoded = TextProperty::read_le(&mut cursor).unwrap();
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+        decoded
+    }
+
+    #[test]
+    fn roundtrip_base() {
+        let data = [
+            0x1f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0x00, 0x55, 0x49, 0x00, 0x04, 0x00, 0x00, 0x00, 0x4b, 0x65, 0x79,
+            0x00, 0x07, 0x00, 0x00, 0x00, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x21, 0x00,
+        ];
+        let decoded = roundtrip(&data);
+        assert_eq!(
+            decoded.value.history,
+            TextHistory::Base {
+                namespace: "UI".to_string(),
+                key: "Key".to_string(),
+                source_string: "Hello!".to_string(),
+            }
+        );
+        assert_eq!(decoded.value.as_str(), Some("Hello!"));
+    }
+
+    #[test]
+    fn roundtrip_none() {
+        let data = [
+            0x13, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0xff,
+            0x01, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x4e, 0x61, 0x6d, 0x65, 0x31, 0x00,
+        ];
+        let decoded = roundtrip(&data);
+        assert_eq!(decoded.value.flags, 2);
+        assert_eq!(decoded.value.as_str(), Some("Name1"));
+
+        let data = [
+            0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let decoded = roundtrip(&data);
+        assert_eq!(decoded.value.as_str(), None);
+    }
+
+    #[test]
+    fn roundtrip_string_table() {
+        let data = [
+            0x1d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0b,
+            0x0c, 0x00, 0x00, 0x00, 0x2f, 0x47, 0x61, 0x6d, 0x65, 0x2f, 0x53, 0x54, 0x5f, 0x55,
+            0x49, 0x00, 0x04, 0x00, 0x00, 0x00, 0x4b, 0x65, 0x79, 0x00,
+        ];
+        let decoded = roundtrip(&data);
+        assert_eq!(
+            decoded.value.history,
+            TextHistory::StringTableEntry {
+                table_id: "/Game/ST_UI".to_string(),
+                key: "Key".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn roundtrip_unknown_history() {
+        // AsNumber, which we don't understand
+        let data = [
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+            0x01, 0x02, 0x03,
+        ];
+        let decoded = roundtrip(&data);
+        assert_eq!(
+            decoded.value.history,
+            TextHistory::Unknown {
+                history_type: 4,
+                raw: vec![0x01, 0x02, 0x03],
+            }
+        );
+    }
+
+    #[test]
+    fn read_unknown_history_with_bad_size() {
+        // Errors from inside the history are only picked out of binrw's enum errors once they reach a property
+        let read = |data: &[u8]| {
+            let mut cursor = Cursor::new(data);
+            let err = in_property(TextProperty::read_le(&mut cursor), "Text", 0).unwrap_err();
+            err.custom_err::<Error>().unwrap().root().clone()
+        };
+
+        // Says it's 4 GiB, but only has 8 bytes
+        let data = [
+            0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+            0x01, 0x02, 0x03,
+        ];
+        assert!(matches!(read(&data), Error::Read { pos: 14, .. }));
+
+        // Too small to even hold the flags and the history type
+        let data = [
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+            0x01, 0x02, 0x03,
+        ];
+        assert_eq!(
+            read(&data),
+            Error::SizeMismatch {
+                pos: 9,
+                expected_size: 2,
+                actual_size: 5,
+            }
+        );
+    }
+}
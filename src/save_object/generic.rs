@@ -1,9 +1,15 @@
-use binrw::{BinRead, BinResult, BinWrite, binrw};
+use std::io::SeekFrom;
+
+use binrw::{BinRead, BinResult, BinWrite, Endian, binrw};
 
 use crate::{
-    common::{read_string_with_length, write_string_with_length},
+    Error,
+    common::{fill_in_size_before, read_string_with_length, write_string_with_length},
+    error::in_property,
     property::{
-        BoolProperty, FloatProperty, IntProperty, NameProperty, StrProperty, StructProperty,
+        BoolProperty, ByteProperty, DoubleProperty, FloatProperty, Int8Property, Int16Property,
+        Int64Property, IntProperty, NameProperty, ObjectProperty, PropertyTag, SoftObjectProperty,
+        StrProperty, StructProperty, TextProperty, UInt16Property, UInt32Property, UInt64Property,
         array_property::ArrayProperty, map_property::MapProperty, set_property::SetProperty,
     },
 };
@@ -11,7 +17,9 @@ use crate::{
 // Used in ArrayProperty exclusively, but could be used instead of magic above
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[br(import { magic: &str, name: &str })]
+#[allow(clippy::large_enum_variant)]
 pub enum Property {
     #[br(pre_assert("NameProperty" == magic))]
     Name(NameProperty),
@@ -21,20 +29,220 @@ pub enum Property {
     Float(FloatProperty),
     #[br(pre_assert("StrProperty" == magic))]
     String(StrProperty),
+    #[br(pre_assert("TextProperty" == magic))]
+    Text(TextProperty),
+    #[br(pre_assert("ObjectProperty" == magic))]
+    Object(ObjectProperty),
+    #[br(pre_assert("SoftObjectProperty" == magic))]
+    SoftObject(SoftObjectProperty),
     #[br(pre_assert("BoolProperty" == magic))]
     Bool(BoolProperty),
     #[br(pre_assert("IntProperty" == magic))]
     Int(IntProperty),
+    #[br(pre_assert("ByteProperty" == magic))]
+    Byte(ByteProperty),
+    #[br(pre_assert("Int8Property" == magic))]
+    Int8(Int8Property),
+    #[br(pre_assert("Int16Property" == magic))]
+    Int16(Int16Property),
+    #[br(pre_assert("Int64Property" == magic))]
+    Int64(Int64Property),
+    #[br(pre_assert("UInt16Property" == magic))]
+    UInt16(UInt16Property),
+    #[br(pre_assert("UInt32Property" == magic))]
+    UInt32(UInt32Property),
+    #[br(pre_assert("UInt64Property" == magic))]
+    UInt64(UInt64Property),
+    #[br(pre_assert("DoubleProperty" == magic))]
+    Double(DoubleProperty),
     #[br(pre_assert("ArrayProperty" == magic))]
     Array(ArrayProperty),
     #[br(pre_assert("MapProperty" == magic))]
     Map(MapProperty),
     #[br(pre_assert("SetProperty" == magic))]
     Set(SetProperty),
+    /// A property type we don't know how to read, kept as-is so it's written back unchanged.
+    #[br(pre_assert(!Property::is_known(magic)))]
+    Unknown {
+        #[br(calc = magic.to_string())]
+        #[bw(ignore)]
+        type_name: String,
+        /// Everything after the type name: the size, array index, GUID and value.
+        #[br(parse_with = read_unknown_property, args(magic))]
+        raw: Vec<u8>,
+    },
+}
+
+impl Property {
+    /// Whether `type_name` is one of the property types we know how to read.
+    pub fn is_known(type_name: &str) -> bool {
+        [
+            "NameProperty",
+            "StructProperty",
+            "FloatProperty",
+            "StrProperty",
+            "TextProperty",
+            "ObjectProperty",
+            "SoftObjectProperty",
+            "BoolProperty",
+            "IntProperty",
+            "ByteProperty",
+            "Int8Property",
+            "Int16Property",
+            "Int64Property",
+            "UInt16Property",
+            "UInt32Property",
+            "UInt64Property",
+            "DoubleProperty",
+            "ArrayProperty",
+            "MapProperty",
+            "SetProperty",
+        ]
+        .contains(&type_name)
+    }
+
+    /// The tag of the property, or `None` if we don't know how to read it.
+    pub fn tag(&self) -> Option<&PropertyTag> {
+        match self {
+            Property::Name(property) => Some(&property.tag),
+            Property::Struct(property) => Some(&property.tag),
+            Property::Float(property) => Some(&property.tag),
+            Property::String(property) => Some(&property.tag),
+            Property::Text(property) => Some(&property.tag),
+            Property::Object(property) => Some(&property.tag),
+            Property::SoftObject(property) => Some(&property.tag),
+            Property::Bool(property) => Some(&property.tag),
+            Property::Int(property) => Some(&property.tag),
+            Property::Byte(property) => Some(&property.tag),
+            Property::Int8(property) => Some(&property.tag),
+            Property::Int16(property) => Some(&property.tag),
+            Property::Int64(property) => Some(&property.tag),
+            Property::UInt16(property) => Some(&property.tag),
+            Property::UInt32(property) => Some(&property.tag),
+            Property::UInt64(property) => Some(&property.tag),
+            Property::Double(property) => Some(&property.tag),
+            Property::Array(property) => Some(&property.tag),
+            Property::Map(property) => Some(&property.tag),
+            Property::Set(property) => Some(&property.tag),
+            Property::Unknown { .. } => None,
+        }
+    }
+
+    /// The type name the property is written with, e.g. `IntProperty`.
+    pub fn type_name(&self) -> &str {
+        match self {
+            Property::Name(_) => "NameProperty",
+            Property::Struct(_) => "StructProperty",
+            Property::Float(_) => "FloatProperty",
+            Property::String(_) => "StrProperty",
+            Property::Text(_) => "TextProperty",
+            Property::Object(_) => "ObjectProperty",
+            Property::SoftObject(_) => "SoftObjectProperty",
+            Property::Bool(_) => "BoolProperty",
+            Property::Int(_) => "IntProperty",
+            Property::Byte(_) => "ByteProperty",
+            Property::Int8(_) => "Int8Property",
+            Property::Int16(_) => "Int16Property",
+            Property::Int64(_) => "Int64Property",
+            Property::UInt16(_) => "UInt16Property",
+            Property::UInt32(_) => "UInt32Property",
+            Property::UInt64(_) => "UInt64Property",
+            Property::Double(_) => "DoubleProperty",
+            Property::Array(_) => "ArrayProperty",
+            Property::Map(_) => "MapProperty",
+            Property::Set(_) => "SetProperty",
+            Property::Unknown { type_name, .. } => type_name,
+        }
+    }
+
+    /// Index of the property in its static array, which is 0 unless the same name is repeated.
+    pub fn array_index(&self) -> u32 {
+        match self {
+            // The raw bytes start with the size and array index
+            Property::Unknown { raw, .. } => raw
+                .get(4..8)
+                .map_or(0, |index| u32::from_le_bytes(index.try_into().unwrap())),
+            property => property.tag().map_or(0, |tag| tag.array_index),
+        }
+    }
+}
+
+/// Reads the rest of a property we don't know about, using the size in its tag.
+#[binrw::parser(reader)]
+fn read_unknown_property(type_name: &str) -> BinResult<Vec<u8>> {
+    let start = reader.stream_position()?;
+    let size_in_bytes = u32::read_le(reader)?;
+    let _array_index = u32::read_le(reader)?;
+    // This has the name of its enum in the tag
+    if type_name == "EnumProperty" {
+        read_string_with_length(reader, Endian::Little, ())?;
+    }
+    let has_guid = u8::read_le(reader)?;
+    if has_guid != 0 {
+        reader.seek(SeekFrom::Current(16))?;
+    }
+    let value_start = reader.stream_position()?;
+
+    // The size comes straight from the file, so make sure there's that much data left before trusting it
+    let data_end = reader.seek(SeekFrom::End(0))?;
+    let remaining = data_end.saturating_sub(value_start);
+    if size_in_bytes as u64 > remaining {
+        return Err(Error::Read {
+            pos: start,
+            message: format!(
+                "property has a size of {size_in_bytes} bytes, but only {remaining} bytes are left"
+            ),
+        }
+        .into());
+    }
+
+    reader.seek(SeekFrom::Start(start))?;
+    let mut raw = vec![0u8; (value_start - start + size_in_bytes as u64) as usize];
+    reader.read_exact(&mut raw)?;
+    Ok(raw)
+}
+
+/// Reads a [Property], falling back to [Property::Unknown] if we don't know about `type_name`.
+#[binrw::parser(reader, endian)]
+pub(crate) fn read_property(type_name: &str, name: &str) -> BinResult<Property> {
+    Property::read_options(
+        reader,
+        endian,
+        binrw::args! { magic: type_name, name: name },
+    )
+}
+
+/// Writes a [Property], after making sure it's of the type that was written in front of it.
+#[binrw::writer(writer, endian)]
+pub(crate) fn write_property(property: &Property, type_name: &str) -> BinResult<()> {
+    if property.type_name() != type_name {
+        return Err(Error::TypeMismatch {
+            pos: writer.stream_position()?,
+            expected: type_name.to_string(),
+            found: property.type_name().to_string(),
+        }
+        .into());
+    }
+    property.write_options(writer, endian, ())
+}
+
+#[binrw::parser(reader, endian)]
+fn read_entry_property(type_name: &str, name: &str) -> BinResult<Option<Property>> {
+    let pos = reader.stream_position()?;
+    in_property(read_property(reader, endian, (type_name, name)), name, pos).map(Some)
+}
+
+#[binrw::writer(writer, endian)]
+fn write_entry_property(property: &Option<Property>, type_name: &str) -> BinResult<()> {
+    match property {
+        Some(property) => write_property(property, writer, endian, (type_name,)),
+        None => Ok(()),
+    }
 }
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entry {
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
@@ -45,10 +253,18 @@ pub struct Entry {
     #[br(if(name != "None"))]
     pub type_name: String,
 
-    #[br(if(name != "None"), args { magic: &type_name, name: &name })]
+    #[br(if(name != "None"), parse_with = read_entry_property, args(&type_name, &name))]
+    #[bw(write_with = write_entry_property, args(type_name))]
     pub r#type: Option<Property>,
 }
 
+impl Entry {
+    /// Index of the entry in its static array, see [Property::array_index].
+    pub fn array_index(&self) -> u32 {
+        self.r#type.as_ref().map_or(0, Property::array_index)
+    }
+}
+
 #[binrw::parser(reader, endian)]
 fn custom_tagged_object_parser(size_in_bytes: u32) -> BinResult<Vec<Entry>> {
     let mut result = Vec::<Entry>::new();
@@ -69,6 +285,7 @@ fn custom_tagged_object_parser(size_in_bytes: u32) -> BinResult<Vec<Entry>> {
 
 #[binrw::writer(writer, endian)]
 fn custom_tagged_object_writer(entries: &Vec<Entry>) -> BinResult<()> {
+    let start = writer.stream_position()?;
     for entry in entries {
         entry.write_options(writer, endian, ())?
     }
@@ -79,12 +296,14 @@ fn custom_tagged_object_writer(entries: &Vec<Entry>) -> BinResult<()> {
         r#type: None,
     };
     none_entry.write_options(writer, endian, ())?;
-    Ok(())
+    fill_in_size_before(writer, endian, start)
 }
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericTaggedObject {
+    /// Size of the entries, this is calculated again when writing.
     pub size_in_bytes: u32,
     #[br(parse_with = custom_tagged_object_parser, args(size_in_bytes))]
     #[bw(write_with = custom_tagged_object_writer)]
@@ -92,8 +311,123 @@ pub struct GenericTaggedObject {
 }
 
 impl GenericTaggedObject {
+    /// The first entry called `key`, for a static array this is the one at index 0.
     pub fn entry(&self, key: &str) -> Option<&Entry> {
-        let entries: Vec<&Entry> = self.entries.iter().filter(|e| e.name == key).collect();
-        entries.first().copied()
+        self.entries.iter().find(|e| e.name == key)
+    }
+
+    /// Every entry called `key`, there's more than one if it's a static array.
+    pub fn entries_named<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Entry> {
+        self.entries.iter().filter(move |e| e.name == key)
+    }
+
+    /// The element at `index` of the static array called `key`.
+    pub fn entry_at(&self, key: &str, index: u32) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.name == key && e.array_index() == index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn roundtrip_unknown_property() {
+        let data = [
+            0x05, 0x00, 0x00, 0x00, 0x54, 0x65, 0x73, 0x74, 0x00, 0x12, 0x00, 0x00, 0x00, 0x46,
+            0x69, 0x65, 0x6c, 0x64, 0x50, 0x61, 0x74, 0x68, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72,
+            0x74, 0x79, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02,
+            0x03, 0x04,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = Entry::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.name, "Test");
+        let Some(Property::Unknown { type_name, raw }) = &decoded.r#type else {
+            panic!("Expected an unknown property, got {:?}", decoded.r#type);
+        };
+        assert_eq!(type_name, "FieldPathProperty");
+        assert_eq!(raw.len(), 13);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+    }
+
+    #[test]
+    fn read_oversized_unknown_property() {
+        // Says it's 4 GiB, but only has 4 bytes
+        let data = [
+            0x05, 0x00, 0x00, 0x00, 0x54, 0x65, 0x73, 0x74, 0x00, 0x12, 0x00, 0x00, 0x00, 0x46,
+            0x69, 0x65, 0x6c, 0x64, 0x50, 0x61, 0x74, 0x68, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72,
+            0x74, 0x79, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02,
+            0x03, 0x04,
+        ];
+        let mut cursor = Cursor::new(data);
+        let err = Entry::read_le(&mut cursor).unwrap_err();
+        let err = err.custom_err::<Error>().unwrap();
+        assert!(matches!(err.root(), Error::Read { pos: 31, .. }));
+    }
+
+    #[test]
+    fn write_mismatched_unknown_property() {
+        let entry = Entry {
+            name: "Test".to_string(),
+            type_name: "FieldPathProperty".to_string(),
+            r#type: Some(Property::Unknown {
+                type_name: "DelegateProperty".to_string(),
+                raw: vec![0x00, 0x00],
+            }),
+        };
+        // Too short to have an array index in it
+        assert_eq!(entry.array_index(), 0);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        let err = entry.write_le(&mut Cursor::new(&mut buffer)).unwrap_err();
+        assert_eq!(
+            err.custom_err::<Error>(),
+            Some(&Error::TypeMismatch {
+                pos: 31,
+                expected: "FieldPathProperty".to_string(),
+                found: "DelegateProperty".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn read_static_array() {
+        let data = [
+            0x5d, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x56, 0x61, 0x6c, 0x75, 0x65, 0x73,
+            0x00, 0x0c, 0x00, 0x00, 0x00, 0x49, 0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72,
+            0x74, 0x79, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x56, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x00, 0x0c,
+            0x00, 0x00, 0x00, 0x49, 0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79,
+            0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x05, 0x00, 0x00, 0x00, 0x4e, 0x6f, 0x6e, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = GenericTaggedObject::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.entries.len(), 2);
+        assert_eq!(decoded.entries_named("Values").count(), 2);
+        assert_eq!(decoded.entry("Values").unwrap().array_index(), 0);
+        let Some(Property::Int(second)) = &decoded.entry_at("Values", 1).unwrap().r#type else {
+            panic!("IntProperty!")
+        };
+        assert_eq!(second.value, 2);
+        assert!(decoded.entry_at("Values", 2).is_none());
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
     }
 }
@@ -1,8 +1,12 @@
-use crate::property::{BoolProperty, IntProperty, StrProperty, map_property::MapProperty};
+use crate::property::{
+    BoolProperty, IntProperty, StrProperty,
+    map_property::{MapEntry, MapProperty},
+};
 
 /// The object stored in `LocalProfile.sav`.
 #[paramacro::serialized_struct("")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LocalProfileObject {
     #[paramacro::serialized_field = "SavedDataVersion"]
     version: IntProperty,
@@ -19,3 +23,50 @@ pub struct LocalProfileObject {
     #[paramacro::serialized_field = "bUseSaveSlot"]
     use_save_slot: BoolProperty,
 }
+
+impl LocalProfileObject {
+    /// Version of the save data.
+    pub fn version(&self) -> i32 {
+        self.version.value
+    }
+
+    /// Whether this was saved by the demo.
+    pub fn is_demo(&self) -> bool {
+        self.demo.value
+    }
+
+    /// Sets whether this was saved by the demo.
+    pub fn set_demo(&mut self, demo: bool) {
+        self.demo.value = demo;
+    }
+
+    /// Names the player has registered.
+    pub fn registered_names(&self) -> &[MapEntry] {
+        &self.name_list.entries
+    }
+
+    /// Names the player has registered, for adding or removing some.
+    pub fn registered_names_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.name_list.entries
+    }
+
+    /// Name of the save game this profile belongs to.
+    pub fn save_game_name(&self) -> &str {
+        &self.name.value
+    }
+
+    /// Sets the name of the save game this profile belongs to.
+    pub fn set_save_game_name(&mut self, name: impl Into<String>) {
+        self.name.value = name.into();
+    }
+
+    /// Whether save slots are used.
+    pub fn use_save_slot(&self) -> bool {
+        self.use_save_slot.value
+    }
+
+    /// Sets whether save slots are used.
+    pub fn set_use_save_slot(&mut self, use_save_slot: bool) {
+        self.use_save_slot.value = use_save_slot;
+    }
+}
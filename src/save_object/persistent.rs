@@ -1,14 +1,19 @@
 use crate::{
     property::{
-        BoolProperty, IntProperty, NameProperty, StrProperty, array_property::ArrayProperty,
-        map_property::MapProperty, set_property::SetProperty,
+        BoolProperty, IntProperty, NameProperty, StrProperty,
+        array_property::{ArrayProperty, ArrayValue},
+        map_property::{MapEntry, MapProperty},
+        set_property::{SetEntry, SetProperty},
+    },
+    structure::{
+        DABuildDataStruct, DATuningPointData, Guid, PrimaryAssetIdStruct, Struct, TransformStruct,
     },
-    structure::{DABuildDataStruct, DATuningPointData, TransformStruct},
 };
 
 /// The object stored in `Persistent.sav`.
 #[paramacro::serialized_struct("")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PersistentObject {
     #[paramacro::serialized_field = "SavedDataVersion"]
     version: IntProperty,
@@ -100,3 +105,362 @@ pub struct PersistentObject {
     #[paramacro::serialized_field = "bUseSaveSlot"]
     use_save_slot: BoolProperty,
 }
+
+impl PersistentObject {
+    /// Version of the save data.
+    pub fn version(&self) -> i32 {
+        self.version.value
+    }
+
+    /// Whether this was saved by the demo.
+    pub fn is_demo(&self) -> bool {
+        self.demo_version.value
+    }
+
+    /// Sets whether this was saved by the demo.
+    pub fn set_demo(&mut self, demo: bool) {
+        self.demo_version.value = demo;
+    }
+
+    /// How much money the player has.
+    pub fn money(&self) -> i32 {
+        self.money.value
+    }
+
+    /// Sets how much money the player has.
+    pub fn set_money(&mut self, money: i32) {
+        self.money.value = money;
+    }
+
+    /// Items the player has ever obtained.
+    pub fn obtained_items(&self) -> &[SetEntry] {
+        &self.obtained_items.entries
+    }
+
+    /// Items the player has ever obtained, for adding or removing some.
+    pub fn obtained_items_mut(&mut self) -> &mut Vec<SetEntry> {
+        &mut self.obtained_items.entries
+    }
+
+    /// Items in the item slots.
+    pub fn item_slots(&self) -> impl Iterator<Item = &PrimaryAssetIdStruct> {
+        self.item_slots
+            .entries
+            .iter()
+            .filter_map(|entry| match &entry.key {
+                ArrayValue::Struct {
+                    r#struct: Struct::PrimaryAssetId(id),
+                } => Some(id),
+                _ => None,
+            })
+    }
+
+    /// Items in the item slots, for changing which item is in a slot.
+    pub fn item_slots_mut(&mut self) -> impl Iterator<Item = &mut PrimaryAssetIdStruct> {
+        self.item_slots
+            .entries
+            .iter_mut()
+            .filter_map(|entry| match &mut entry.key {
+                ArrayValue::Struct {
+                    r#struct: Struct::PrimaryAssetId(id),
+                } => Some(id),
+                _ => None,
+            })
+    }
+
+    /// Index of the item slot that's selected.
+    pub fn current_item_slot(&self) -> i32 {
+        self.current_item_slot.value
+    }
+
+    /// Sets the index of the item slot that's selected.
+    pub fn set_current_item_slot(&mut self, slot: i32) {
+        self.current_item_slot.value = slot;
+    }
+
+    /// Items the player has, with how many of each.
+    pub fn normal_item_inventory(&self) -> &[MapEntry] {
+        &self.normal_item_inventory.entries
+    }
+
+    /// Items the player has, for changing them or how many there are.
+    pub fn normal_item_inventory_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.normal_item_inventory.entries
+    }
+
+    /// Modules the player has, by their id.
+    pub fn module_inventory(&self) -> &[MapEntry] {
+        &self.module_inventory.entries
+    }
+
+    /// Modules the player has, for changing them.
+    pub fn module_inventory_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.module_inventory.entries
+    }
+
+    /// Parts the player has, with how many of each.
+    pub fn parts_inventory(&self) -> &[MapEntry] {
+        &self.parts_inventory.entries
+    }
+
+    /// Parts the player has, for changing them or how many there are.
+    pub fn parts_inventory_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.parts_inventory.entries
+    }
+
+    /// The build that's currently equipped.
+    pub fn current_build_data(&self) -> &DABuildDataStruct {
+        &self.current_build_data
+    }
+
+    /// The build that's currently equipped, for changing it.
+    pub fn current_build_data_mut(&mut self) -> &mut DABuildDataStruct {
+        &mut self.current_build_data
+    }
+
+    /// Builds the player has saved.
+    pub fn saved_build_data(&self) -> impl Iterator<Item = &DABuildDataStruct> {
+        self.saved_build_data
+            .entries
+            .iter()
+            .filter_map(|entry| match &entry.key {
+                ArrayValue::Struct {
+                    r#struct: Struct::DABuildData(build),
+                } => Some(build),
+                _ => None,
+            })
+    }
+
+    /// Builds the player has saved, for changing them.
+    pub fn saved_build_data_mut(&mut self) -> impl Iterator<Item = &mut DABuildDataStruct> {
+        self.saved_build_data
+            .entries
+            .iter_mut()
+            .filter_map(|entry| match &mut entry.key {
+                ArrayValue::Struct {
+                    r#struct: Struct::DABuildData(build),
+                } => Some(build),
+                _ => None,
+            })
+    }
+
+    /// Color palettes the player has saved, by their index.
+    pub fn palettes(&self) -> &[MapEntry] {
+        &self.palettes.entries
+    }
+
+    /// Color palettes the player has saved, for changing them.
+    pub fn palettes_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.palettes.entries
+    }
+
+    /// How many tuning points the player has to spend.
+    pub fn tuning_points(&self) -> i32 {
+        self.tuning_point_data.tuning_point.value
+    }
+
+    /// Sets how many tuning points the player has to spend.
+    pub fn set_tuning_points(&mut self, points: i32) {
+        self.tuning_point_data.tuning_point.value = points;
+    }
+
+    /// The most tuning points the player can have.
+    pub fn max_tuning_points(&self) -> i32 {
+        self.tuning_point_data.max_tuning_point.value
+    }
+
+    /// Sets the most tuning points the player can have.
+    pub fn set_max_tuning_points(&mut self, points: i32) {
+        self.tuning_point_data.max_tuning_point.value = points;
+    }
+
+    /// Values of the event parameters, by name.
+    pub fn event_params(&self) -> &[MapEntry] {
+        &self.event_params.entries
+    }
+
+    /// Values of the event parameters, for changing them.
+    pub fn event_params_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.event_params.entries
+    }
+
+    /// Tags of the districts the player has reached, like `District_M01A01_001`.
+    pub fn reached_districts(&self) -> impl Iterator<Item = &str> {
+        self.reached_districts.names()
+    }
+
+    /// Marks the district with `tag` as reached, returning false if it already was.
+    pub fn insert_reached_district(&mut self, tag: impl Into<String>) -> bool {
+        self.reached_districts.insert_name(tag)
+    }
+
+    /// Marks the district with `tag` as not reached, returning false if it already wasn't.
+    pub fn remove_reached_district(&mut self, tag: &str) -> bool {
+        self.reached_districts.remove_name(tag)
+    }
+
+    /// How many of each shop item the player has bought, by item name.
+    pub fn shop_bought_count(&self) -> &[MapEntry] {
+        &self.shop_bought_count.entries
+    }
+
+    /// How many of each shop item the player has bought, for changing them.
+    pub fn shop_bought_count_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.shop_bought_count.entries
+    }
+
+    /// Ids of the item boxes the player has picked up.
+    pub fn acquired_item_box_ids(&self) -> impl Iterator<Item = &Guid> {
+        self.acquired_item_box_ids.guids()
+    }
+
+    /// Marks the item box with `id` as picked up, returning false if it already was.
+    pub fn insert_acquired_item_box_id(&mut self, id: Guid) -> bool {
+        self.acquired_item_box_ids.insert_guid(id)
+    }
+
+    /// Marks the item box with `id` as not picked up, returning false if it already wasn't.
+    pub fn remove_acquired_item_box_id(&mut self, id: &Guid) -> bool {
+        self.acquired_item_box_ids.remove_guid(id)
+    }
+
+    /// Ids of the strong boxes the player has opened.
+    pub fn opened_strong_box_ids(&self) -> impl Iterator<Item = &Guid> {
+        self.opened_strong_box_ids.guids()
+    }
+
+    /// Marks the strong box with `id` as opened, returning false if it already was.
+    pub fn insert_opened_strong_box_id(&mut self, id: Guid) -> bool {
+        self.opened_strong_box_ids.insert_guid(id)
+    }
+
+    /// Marks the strong box with `id` as not opened, returning false if it already wasn't.
+    pub fn remove_opened_strong_box_id(&mut self, id: &Guid) -> bool {
+        self.opened_strong_box_ids.remove_guid(id)
+    }
+
+    /// Tag of the player start to go back to.
+    pub fn regression_player_start_tag(&self) -> &str {
+        &self.regression_player_start_tag.value
+    }
+
+    /// Sets the tag of the player start to go back to.
+    pub fn set_regression_player_start_tag(&mut self, tag: impl Into<String>) {
+        self.regression_player_start_tag.value = tag.into();
+    }
+
+    /// Name of the level to go back to.
+    pub fn regression_level_name(&self) -> &str {
+        &self.regression_level_name.value
+    }
+
+    /// Sets the name of the level to go back to.
+    pub fn set_regression_level_name(&mut self, name: impl Into<String>) {
+        self.regression_level_name.value = name.into();
+    }
+
+    /// Whether the game starts from the regression point, instead of where the player was.
+    pub fn start_from_regression_point(&self) -> bool {
+        self.start_from_regression_point.value
+    }
+
+    /// Sets whether the game starts from the regression point, instead of where the player was.
+    pub fn set_start_from_regression_point(&mut self, start: bool) {
+        self.start_from_regression_point.value = start;
+    }
+
+    /// Names of the checkpoints the player has released, like `Checkpoint_M01A01_001`.
+    pub fn released_checkpoints(&self) -> impl Iterator<Item = &str> {
+        self.released_checkpoints.names()
+    }
+
+    /// Marks the checkpoint called `name` as released, returning false if it already was.
+    pub fn insert_released_checkpoint(&mut self, name: impl Into<String>) -> bool {
+        self.released_checkpoints.insert_name(name)
+    }
+
+    /// Marks the checkpoint called `name` as not released, returning false if it already wasn't.
+    pub fn remove_released_checkpoint(&mut self, name: &str) -> bool {
+        self.released_checkpoints.remove_name(name)
+    }
+
+    /// Where the player was when the game was suspended.
+    pub fn suspend_transform(&self) -> &TransformStruct {
+        &self.suspend_transform
+    }
+
+    /// Where the player was when the game was suspended, for moving them.
+    pub fn suspend_transform_mut(&mut self) -> &mut TransformStruct {
+        &mut self.suspend_transform
+    }
+
+    /// What's kept about each character, like whether the player has met them.
+    pub fn character_persistent_data_list(&self) -> &[MapEntry] {
+        &self.character_persistent_data_list.entries
+    }
+
+    /// What's kept about each character, for changing it.
+    pub fn character_persistent_data_list_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.character_persistent_data_list.entries
+    }
+
+    /// State of each boss, like `EDABossState::Defeated`.
+    pub fn boss_states(&self) -> &[MapEntry] {
+        &self.boss_states.entries
+    }
+
+    /// State of each boss, for changing them.
+    pub fn boss_states_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.boss_states.entries
+    }
+
+    /// State of each NPC.
+    pub fn npc_states(&self) -> &[MapEntry] {
+        &self.npc_states.entries
+    }
+
+    /// State of each NPC, for changing them.
+    pub fn npc_states_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.npc_states.entries
+    }
+
+    /// Dialogues the player has read, by their asset.
+    pub fn read_dialogues(&self) -> &[MapEntry] {
+        &self.read_dialogues.entries
+    }
+
+    /// Dialogues the player has read, for changing them.
+    pub fn read_dialogues_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.read_dialogues.entries
+    }
+
+    /// Dialogue chains the player has read, by name.
+    pub fn read_dialogue_chains(&self) -> &[MapEntry] {
+        &self.read_dialogue_chains.entries
+    }
+
+    /// Dialogue chains the player has read, for changing them.
+    pub fn read_dialogue_chains_mut(&mut self) -> &mut Vec<MapEntry> {
+        &mut self.read_dialogue_chains.entries
+    }
+
+    /// Name of the save game.
+    pub fn save_game_name(&self) -> &str {
+        &self.save_game_name.value
+    }
+
+    /// Sets the name of the save game.
+    pub fn set_save_game_name(&mut self, name: impl Into<String>) {
+        self.save_game_name.value = name.into();
+    }
+
+    /// Whether save slots are used.
+    pub fn use_save_slot(&self) -> bool {
+        self.use_save_slot.value
+    }
+
+    /// Sets whether save slots are used.
+    pub fn set_use_save_slot(&mut self, use_save_slot: bool) {
+        self.use_save_slot.value = use_save_slot;
+    }
+}
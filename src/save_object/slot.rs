@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::{
     property::{BoolProperty, FloatProperty, IntProperty, NameProperty, StrProperty},
     structure::{DALoadOptionStruct, DateTimeStruct, SaveSlotInfoStruct},
@@ -6,6 +8,7 @@ use crate::{
 /// The object stored in `Slot.sav`.
 #[paramacro::serialized_struct("")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotObject {
     #[paramacro::serialized_field = "SavedDataVersion"]
     version: IntProperty,
@@ -34,3 +37,91 @@ pub struct SlotObject {
     #[paramacro::serialized_field = "SlotInfo"]
     slot_info: SaveSlotInfoStruct,
 }
+
+impl SlotObject {
+    /// Version of the save data.
+    pub fn version(&self) -> i32 {
+        self.version.value
+    }
+
+    /// Whether this was saved by the demo.
+    pub fn is_demo(&self) -> bool {
+        self.demo.value
+    }
+
+    /// Sets whether this was saved by the demo.
+    pub fn set_demo(&mut self, demo: bool) {
+        self.demo.value = demo;
+    }
+
+    /// When the slot was created.
+    pub fn created_timestamp(&self) -> &DateTimeStruct {
+        &self.created_timestamp
+    }
+
+    /// Sets when the slot was created.
+    pub fn set_created_timestamp(&mut self, timestamp: DateTimeStruct) {
+        self.created_timestamp = timestamp;
+    }
+
+    /// How long the slot has been played for.
+    pub fn play_time(&self) -> Duration {
+        // Negative or broken values are treated as no play time at all
+        Duration::try_from_secs_f32(self.playtime.value).unwrap_or_default()
+    }
+
+    /// Sets the play time, which is stored as seconds in a float so it loses some precision.
+    pub fn set_play_time(&mut self, play_time: Duration) {
+        self.playtime.value = play_time.as_secs_f32();
+    }
+
+    /// Name the player gave to the slot.
+    pub fn slot_name(&self) -> &str {
+        &self.name.value
+    }
+
+    /// Sets the name of the slot.
+    pub fn set_slot_name(&mut self, name: impl Into<String>) {
+        self.name.value = name.into();
+    }
+
+    /// The load types to use when the slot is loaded.
+    pub fn load_types(&self) -> i32 {
+        self.load_option.load_types.value
+    }
+
+    /// Sets the load types to use when the slot is loaded.
+    pub fn set_load_types(&mut self, load_types: i32) {
+        self.load_option.load_types.value = load_types;
+    }
+
+    /// Tag of the district the player is in, like `District_M01A01_001`.
+    pub fn district_tag(&self) -> &str {
+        &self.district_tag.value
+    }
+
+    /// Sets the tag of the district the player is in.
+    pub fn set_district_tag(&mut self, tag: impl Into<String>) {
+        self.district_tag.value = tag.into();
+    }
+
+    /// How many cycles the player has gone through.
+    pub fn cycle_count(&self) -> i32 {
+        self.cycle_count.value
+    }
+
+    /// Sets how many cycles the player has gone through.
+    pub fn set_cycle_count(&mut self, count: i32) {
+        self.cycle_count.value = count;
+    }
+
+    /// Information shown in the slot list.
+    pub fn slot_info(&self) -> &SaveSlotInfoStruct {
+        &self.slot_info
+    }
+
+    /// Information shown in the slot list, for changing it.
+    pub fn slot_info_mut(&mut self) -> &mut SaveSlotInfoStruct {
+        &mut self.slot_info
+    }
+}
@@ -10,6 +10,7 @@ use super::{
 
 #[paramacro::serialized_struct("DABuildData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DABuildDataStruct {
     #[paramacro::serialized_field = "Name"]
     pub name: StrProperty,
@@ -27,6 +28,18 @@ pub struct DABuildDataStruct {
     pub tuning: DATuningDataStruct,
 }
 
+impl DABuildDataStruct {
+    /// Name the player gave to the build, which is empty for the one that's equipped.
+    pub fn name(&self) -> &str {
+        &self.name.value
+    }
+
+    /// Sets the name of the build.
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name.value = name.into();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -634,6 +647,6 @@ mod tests {
             0x00, 0x00, 0x00, 0x00,
         ];
         let mut cursor = Cursor::new(data);
-        let decoded = DABuildDataStruct::read_le(&mut cursor).unwrap();
+        DABuildDataStruct::read_le(&mut cursor).unwrap();
     }
 }
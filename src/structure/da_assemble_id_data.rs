@@ -2,6 +2,7 @@ use super::{da_machine_coloring_data::DAMachineColoringDataStruct, guid::Guid};
 
 #[paramacro::serialized_struct("DAAssembleIdData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAAssembleIdDataStruct {
     #[paramacro::serialized_field = "Hanger"]
     pub hanger: Guid,
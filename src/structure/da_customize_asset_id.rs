@@ -7,6 +7,7 @@ use super::{
 
 #[paramacro::serialized_struct("DACustomizeAssetIdData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DACustomizeAssetIdDataStruct {
     #[paramacro::serialized_field = "Body"]
     pub body: PrimaryAssetIdStruct,
@@ -2,6 +2,7 @@ use super::linear_color::LinearColorStruct;
 
 #[paramacro::serialized_struct("DAHumanoidColoringData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAHumanoidColoringDataStruct {
     #[paramacro::serialized_field = "Skin"]
     pub skin: LinearColorStruct,
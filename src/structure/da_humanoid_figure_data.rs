@@ -2,6 +2,7 @@ use crate::property::FloatProperty;
 
 #[paramacro::serialized_struct("DAHumanoidFigureData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAHumanoidFigureData {
     #[paramacro::serialized_field = "BustUp"]
     pub bust_up: FloatProperty,
@@ -2,6 +2,7 @@ use crate::property::IntProperty;
 
 #[paramacro::serialized_struct("DALoadOption")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DALoadOptionStruct {
     #[paramacro::serialized_field = "LoadTypes"]
     pub load_types: IntProperty,
@@ -2,6 +2,7 @@ use super::da_module_color::DAModuleColorStruct;
 
 #[paramacro::serialized_struct("DAMachineColoringData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAMachineColoringDataStruct {
     #[paramacro::serialized_field = "Hanger"]
     pub hanger: DAModuleColorStruct,
@@ -2,6 +2,7 @@ use super::linear_color::LinearColorStruct;
 
 #[paramacro::serialized_struct("DAModuleColor")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAModuleColorStruct {
     #[paramacro::serialized_field = "Main"]
     pub main: LinearColorStruct,
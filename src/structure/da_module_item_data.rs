@@ -2,6 +2,7 @@ use crate::property::IntProperty;
 
 #[paramacro::serialized_struct("DAModuleItemData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAModuleItemDataStruct {
     #[paramacro::serialized_field = "ModuleLevel"]
     pub module_level: IntProperty,
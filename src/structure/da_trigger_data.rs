@@ -2,6 +2,7 @@ use crate::property::EnumProperty;
 
 #[paramacro::serialized_struct("DATriggerData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DATriggerDataStruct {
     #[paramacro::serialized_field = "A"]
     pub a: EnumProperty,
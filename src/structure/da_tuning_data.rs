@@ -2,6 +2,7 @@ use crate::property::map_property::MapProperty;
 
 #[paramacro::serialized_struct("DATuningData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DATuningDataStruct {
     #[paramacro::serialized_field = "GrantedTuningPointList"]
     pub granted_tuning_point_list: MapProperty,
@@ -2,10 +2,11 @@ use crate::property::IntProperty;
 
 #[paramacro::serialized_struct("DATuningPointData")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DATuningPointData {
     #[paramacro::serialized_field = "TuningPoint"]
-    tuning_point: IntProperty,
+    pub tuning_point: IntProperty,
 
     #[paramacro::serialized_field = "MaxTuningPoint"]
-    max_tuning_point: IntProperty,
+    pub max_tuning_point: IntProperty,
 }
@@ -7,6 +7,7 @@ use crate::property::PropertyBase;
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/Core/Misc/FDateTime?application_version=4.27).
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DateTimeStruct {
     /// Number of ticks.
     /// The ticks are 0.1 microseconds (= 100 nanoseconds) since January 1, 0001.
@@ -7,6 +7,7 @@ use crate::property::PropertyBase;
 ///
 /// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/Core/Misc/FGuid?application_version=4.27).
 #[binrw]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct Guid {
     /// Private.
     pub a: u32,
@@ -40,3 +41,53 @@ impl fmt::Debug for Guid {
         ))
     }
 }
+
+/// Written as 32 hex digits, like `69D989EC47DA6190ADC4E1800095F96E`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Guid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!(
+            "{:08X}{:08X}{:08X}{:08X}",
+            self.a, self.b, self.c, self.d
+        ))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Guid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        // from_str_radix also takes a sign, so check the digits first
+        let is_valid = string.len() == 32 && string.bytes().all(|x| x.is_ascii_hexdigit());
+        let part = |i: usize| u32::from_str_radix(&string[i * 8..(i + 1) * 8], 16).ok();
+        match is_valid.then(|| (part(0), part(1), part(2), part(3))) {
+            Some((Some(a), Some(b), Some(c), Some(d))) => Ok(Self { a, b, c, d }),
+            _ => Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&string),
+                &"32 hex digits",
+            )),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_guid() {
+        let guid = Guid {
+            a: 0x69d989ec,
+            b: 0x47da6190,
+            c: 0xadc4e180,
+            d: 0x0095f96e,
+        };
+        let json = serde_json::to_string(&guid).unwrap();
+        assert_eq!(json, "\"69D989EC47DA6190ADC4E1800095F96E\"");
+        assert_eq!(serde_json::from_str::<Guid>(&json).unwrap(), guid);
+
+        assert!(serde_json::from_str::<Guid>("\"69D989EC\"").is_err());
+        assert!(serde_json::from_str::<Guid>("\"+9D989EC47DA6190ADC4E1800095F96E\"").is_err());
+        assert!(serde_json::from_str::<Guid>("\"69D989EC47DA6190ADC4E180009ÿF96\"").is_err());
+    }
+}
@@ -1,9 +1,15 @@
-use binrw::{BinRead, BinResult, BinWrite, binrw};
+use binrw::{BinRead, BinResult, BinWrite, Endian, binrw};
 use std::fmt::Debug;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use crate::{
+    Error,
     common::{read_string_with_length, write_string_with_length},
-    property::PropertyBase,
+    error::in_property,
+    property::{
+        GenericProperty, PropertyBase, PropertyTag, looks_like_properties,
+        read_properties_until_none, read_tag_end, write_properties_until_none, write_tag_end,
+    },
 };
 
 mod build_data;
@@ -51,6 +57,9 @@ pub use self::guid::Guid;
 mod linear_color;
 pub use self::linear_color::LinearColorStruct;
 
+mod object_path;
+pub use self::object_path::{ObjectPath, SoftObjectPath};
+
 mod primary_asset_id;
 pub use self::primary_asset_id::PrimaryAssetIdStruct;
 
@@ -71,7 +80,9 @@ pub use self::vector::VectorStruct;
 
 #[binrw]
 #[derive(Debug)]
-#[br(import { magic: &str })]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[br(import { magic: &str, raw_size: Option<u32> })]
+#[allow(clippy::large_enum_variant)]
 pub enum Struct {
     #[br(pre_assert(magic == DateTimeStruct::struct_name().unwrap()))]
     DateTime(DateTimeStruct),
@@ -115,6 +126,79 @@ pub enum Struct {
     Quat(QuatStruct),
     #[br(pre_assert(magic == VectorStruct::struct_name().unwrap()))]
     Vector(VectorStruct),
+    /// A struct we don't know about, but is made up of tagged properties like most of them.
+    #[br(pre_assert(!Struct::is_known(magic) && raw_size.is_none()))]
+    Unknown {
+        #[br(calc = magic.to_string())]
+        #[bw(ignore)]
+        struct_name: String,
+        #[br(parse_with = read_properties_until_none)]
+        #[bw(write_with = write_properties_until_none)]
+        properties: Vec<GenericProperty>,
+    },
+    /// A struct we don't know about that's serialized natively, so we can only keep its bytes.
+    #[br(pre_assert(!Struct::is_known(magic) && raw_size.is_some()))]
+    UnknownRaw {
+        #[br(calc = magic.to_string())]
+        #[bw(ignore)]
+        struct_name: String,
+        #[br(count = raw_size.unwrap_or_default())]
+        raw: Vec<u8>,
+    },
+}
+
+impl Struct {
+    /// Whether `struct_name` is one of the structs we know how to read.
+    pub fn is_known(struct_name: &str) -> bool {
+        [
+            DateTimeStruct::struct_name(),
+            DALoadOptionStruct::struct_name(),
+            SaveSlotInfoStruct::struct_name(),
+            PrimaryAssetTypeStruct::struct_name(),
+            PrimaryAssetIdStruct::struct_name(),
+            DAModuleItemDataStruct::struct_name(),
+            DABuildDataStruct::struct_name(),
+            DAAssembleIdDataStruct::struct_name(),
+            Guid::struct_name(),
+            DAMachineColoringDataStruct::struct_name(),
+            DAModuleColorStruct::struct_name(),
+            LinearColorStruct::struct_name(),
+            DATriggerDataStruct::struct_name(),
+            DACustomizeAssetIdDataStruct::struct_name(),
+            DATuningDataStruct::struct_name(),
+            DAHumanoidColoringDataStruct::struct_name(),
+            DAHumanoidFigureData::struct_name(),
+            DATuningPointData::struct_name(),
+            TransformStruct::struct_name(),
+            QuatStruct::struct_name(),
+            VectorStruct::struct_name(),
+        ]
+        .contains(&Some(struct_name))
+    }
+}
+
+/// Reads a [Struct], falling back to [Struct::Unknown] or [Struct::UnknownRaw] if we don't know about `struct_name`.
+///
+/// Native structs we don't know about can only be kept if `size_in_bytes` is given, otherwise this errors out.
+#[binrw::parser(reader, endian)]
+pub(crate) fn read_struct(struct_name: &str, size_in_bytes: Option<u32>) -> BinResult<Struct> {
+    let pos = reader.stream_position()?;
+    let mut raw_size = None;
+    if !Struct::is_known(struct_name) && !looks_like_properties(reader)? {
+        if size_in_bytes.is_none() {
+            return Err(Error::UnknownStruct {
+                pos,
+                struct_name: struct_name.to_string(),
+            }
+            .into());
+        }
+        raw_size = size_in_bytes;
+    }
+    Struct::read_options(
+        reader,
+        endian,
+        binrw::args! { magic: struct_name, raw_size: raw_size },
+    )
 }
 
 pub(crate) fn calc_size_in_bytes(r#struct: &Struct) -> u32 {
@@ -155,9 +239,21 @@ pub(crate) fn calc_size_in_bytes(r#struct: &Struct) -> u32 {
         Struct::Transform(transform_struct) => transform_struct.size_in_bytes(),
         Struct::Quat(quat_struct) => quat_struct.size_in_bytes(),
         Struct::Vector(vector_struct) => vector_struct.size_in_bytes(),
+        Struct::Unknown { properties, .. } => calc_properties_size_in_bytes(properties),
+        Struct::UnknownRaw { raw, .. } => raw.len() as u32,
     }
 }
 
+/// Size of a list of tagged properties, including the "None" at the end.
+pub(crate) fn calc_properties_size_in_bytes(properties: &[GenericProperty]) -> u32 {
+    // There's no way to know the size of every property type, so measure it instead
+    let mut buffer = Cursor::new(Vec::new());
+    properties
+        .write_le(&mut buffer)
+        .expect("Writing to memory can't fail");
+    buffer.get_ref().len() as u32 + crate::common::size_of_string_with_length("None")
+}
+
 #[binrw]
 #[derive(Debug)]
 pub(crate) struct StructFieldPrelude {
@@ -173,37 +269,224 @@ pub(crate) struct StructFieldPrelude {
 #[binrw]
 #[derive(Debug)]
 pub(crate) struct StructPrelude {
-    pub size_in_bytes: u32,
-    #[brw(pad_before = 4)]
+    #[br(temp)]
+    #[bw(calc = tag.size_in_bytes)]
+    size_in_bytes: u32,
+
+    #[br(temp)]
+    #[bw(calc = tag.array_index)]
+    array_index: u32,
+
     #[br(parse_with = read_string_with_length)]
     #[bw(write_with = write_string_with_length)]
-    #[brw(pad_after = 17)]
     pub struct_name: String,
+
+    #[br(temp)]
+    #[bw(calc = tag.struct_guid.unwrap_or_default())]
+    struct_guid: Guid,
+
+    #[br(parse_with = read_tag_end, args(size_in_bytes, array_index, Some(struct_guid)))]
+    #[bw(write_with = write_tag_end)]
+    pub tag: PropertyTag,
 }
 
+/// A field of a typed struct, either a single property or a static array of them.
+///
+/// A static array is the same property repeated `N` times with increasing array indices.
+pub(crate) trait StructField: Sized {
+    /// What's kept of the tags of the field, see [read_struct_field].
+    type Tags: Debug;
+
+    fn read_field<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        name: &str,
+    ) -> BinResult<(Self, Self::Tags)>;
+
+    fn write_field<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        name: &str,
+        tags: &Self::Tags,
+    ) -> BinResult<()>;
+
+    /// Size of the field, including the name and tag of every element.
+    fn field_size_in_bytes(&self, name: &str, tags: &Self::Tags) -> u32;
+}
+
+impl<T: PropertyBase + BinRead<Args<'static> = ()> + BinWrite<Args<'static> = ()> + Debug>
+    StructField for T
+{
+    type Tags = Option<PropertyTag>;
+
+    fn read_field<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        name: &str,
+    ) -> BinResult<(Self, Self::Tags)> {
+        read_struct_field_value(reader, endian, (name, 0))
+    }
+
+    fn write_field<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        name: &str,
+        tags: &Self::Tags,
+    ) -> BinResult<()> {
+        write_struct_field_value(self, writer, endian, (name, None, tags))
+    }
+
+    fn field_size_in_bytes(&self, name: &str, tags: &Self::Tags) -> u32 {
+        self.size_in_bytes()
+            + calc_struct_field_prelude_byte_size(T::type_name(), name, T::struct_name())
+            + calc_struct_field_tag_byte_size(tags)
+    }
+}
+
+impl<
+    T: PropertyBase + BinRead<Args<'static> = ()> + BinWrite<Args<'static> = ()> + Debug,
+    const N: usize,
+> StructField for [T; N]
+{
+    type Tags = Vec<Option<PropertyTag>>;
+
+    fn read_field<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        name: &str,
+    ) -> BinResult<(Self, Self::Tags)> {
+        let mut values = Vec::with_capacity(N);
+        let mut tags = Vec::with_capacity(N);
+        for i in 0..N {
+            let pos = reader.stream_position()?;
+            let (value, tag) = in_property(
+                read_struct_field_value(reader, endian, (name, i as u32)),
+                &format!("[{i}]"),
+                pos,
+            )?;
+            values.push(value);
+            tags.push(tag);
+        }
+        Ok((values.try_into().unwrap(), tags))
+    }
+
+    fn write_field<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        name: &str,
+        tags: &Self::Tags,
+    ) -> BinResult<()> {
+        for (i, value) in self.iter().enumerate() {
+            let tag = tags.get(i).cloned().flatten();
+            write_struct_field_value(value, writer, endian, (name, Some(i as u32), &tag))?;
+        }
+        Ok(())
+    }
+
+    fn field_size_in_bytes(&self, name: &str, tags: &Self::Tags) -> u32 {
+        self.iter()
+            .map(|value| {
+                value.size_in_bytes()
+                    + calc_struct_field_prelude_byte_size(T::type_name(), name, T::struct_name())
+            })
+            .sum::<u32>()
+            + tags
+                .iter()
+                .map(calc_struct_field_tag_byte_size)
+                .sum::<u32>()
+    }
+}
+
+/// Reads a field of a struct, along with its tag if it's a struct itself.
+///
+/// Other property types keep their tag in their value, but structs are also used in arrays and maps where they don't have one.
 #[binrw::parser(reader, endian)]
-pub(crate) fn read_struct_field<T: BinRead<Args<'static> = ()> + Debug>(
+pub(crate) fn read_struct_field<T: StructField>(name: &str) -> BinResult<(T, T::Tags)> {
+    let pos = reader.stream_position()?;
+    in_property(T::read_field(reader, endian, name), name, pos)
+}
+
+#[binrw::parser(reader, endian)]
+fn read_struct_field_value<T: PropertyBase + BinRead<Args<'static> = ()> + Debug>(
     name: &str,
-) -> BinResult<T> {
+    array_index: u32,
+) -> BinResult<(T, Option<PropertyTag>)> {
+    let pos = reader.stream_position()?;
     let prelude = StructFieldPrelude::read_le(reader)?;
     if prelude.property_name != name {
-        panic!(
-            "Property name doesn't match! Is supposed to be {} but is actually {}",
-            name, prelude.property_name
-        );
+        return Err(Error::NameMismatch {
+            pos,
+            expected: name.to_string(),
+            found: prelude.property_name,
+        }
+        .into());
+    }
+    if prelude.type_name != T::type_name() {
+        return Err(Error::TypeMismatch {
+            pos,
+            expected: T::type_name().to_string(),
+            found: prelude.type_name,
+        }
+        .into());
     }
-    // TODO: type check with type_name()
     if prelude.type_name == "StructProperty" {
-        StructPrelude::read_le(reader)?;
+        let struct_pos = reader.stream_position()?;
+        let struct_prelude = StructPrelude::read_le(reader)?;
+        if Some(struct_prelude.struct_name.as_str()) != T::struct_name() {
+            return Err(Error::TypeMismatch {
+                pos: struct_pos,
+                expected: T::struct_name().unwrap_or_default().to_string(),
+                found: struct_prelude.struct_name,
+            }
+            .into());
+        }
+        check_array_index(struct_pos, array_index, struct_prelude.tag.array_index)?;
+        let val = T::read_options(reader, endian, ())?;
+        Ok((val, Some(struct_prelude.tag)))
+    } else {
+        // Every other property has it right after its size, which is read together with the value
+        let tag_pos = reader.stream_position()?;
+        reader.seek(SeekFrom::Current(4))?;
+        let found = u32::read_options(reader, endian, ())?;
+        reader.seek(SeekFrom::Start(tag_pos))?;
+        check_array_index(tag_pos, array_index, found)?;
+        let val = T::read_options(reader, endian, ())?;
+        Ok((val, None))
+    }
+}
+
+fn check_array_index(pos: u64, expected: u32, found: u32) -> BinResult<()> {
+    if expected != found {
+        return Err(Error::ArrayIndexMismatch {
+            pos,
+            expected,
+            found,
+        }
+        .into());
     }
-    let val = T::read_options(reader, endian, ())?;
-    Ok(val)
+    Ok(())
 }
 
+/// Writes a field of a struct, see [read_struct_field].
 #[binrw::writer(writer, endian)]
-pub(crate) fn write_struct_field<T: PropertyBase + BinWrite<Args<'static> = ()> + Debug>(
+pub(crate) fn write_struct_field<T: StructField>(
     structure: &T,
     name: &str,
+    tags: &T::Tags,
+) -> BinResult<()> {
+    structure.write_field(writer, endian, name, tags)
+}
+
+/// Writes a single field, `array_index` overrides the one in the tag of the value if it's set.
+#[binrw::writer(writer, endian)]
+fn write_struct_field_value<T: PropertyBase + BinWrite<Args<'static> = ()> + Debug>(
+    structure: &T,
+    name: &str,
+    array_index: Option<u32>,
+    tag: &Option<PropertyTag>,
 ) -> BinResult<()> {
     let prelude = StructFieldPrelude {
         property_name: name.to_string(),
@@ -211,13 +494,28 @@ pub(crate) fn write_struct_field<T: PropertyBase + BinWrite<Args<'static> = ()>
     };
     prelude.write_le(writer)?;
     if T::type_name() == "StructProperty" {
+        let tag = tag.clone().unwrap_or_default();
         let struct_prelude = StructPrelude {
-            size_in_bytes: T::size_in_bytes(structure),
             struct_name: T::struct_name().unwrap().to_string(),
+            tag: PropertyTag {
+                size_in_bytes: T::size_in_bytes(structure),
+                array_index: array_index.unwrap_or(tag.array_index),
+                ..tag
+            },
         };
         struct_prelude.write_le(writer)?;
+        structure.write_options(writer, endian, ())?;
+    } else {
+        let tag_pos = writer.stream_position()?;
+        structure.write_options(writer, endian, ())?;
+        // The array index is in the tag the value wrote, right after its size
+        if let Some(array_index) = array_index {
+            let end = writer.stream_position()?;
+            writer.seek(SeekFrom::Start(tag_pos + 4))?;
+            array_index.write_options(writer, endian, ())?;
+            writer.seek(SeekFrom::Start(end))?;
+        }
     }
-    structure.write_options(writer, endian, ())?;
     Ok(())
 }
 
@@ -229,9 +527,9 @@ pub(crate) fn calc_struct_field_prelude_byte_size(
     let mut base_size = crate::common::size_of_string_with_length(field_name);
 
     // This is an easy way to detect properties that are actually structs
-    if struct_name.is_some() {
+    if let Some(struct_name) = struct_name {
         base_size += crate::common::size_of_string_with_length("StructProperty")
-            + crate::common::size_of_string_with_length(struct_name.unwrap())
+            + crate::common::size_of_string_with_length(struct_name)
             + 4
             + 17
             + 4; // see struct prelude
@@ -241,3 +539,124 @@ pub(crate) fn calc_struct_field_prelude_byte_size(
 
     base_size
 }
+
+/// Size of the parts of a struct field's tag that aren't always there, which is just the property GUID.
+pub(crate) fn calc_struct_field_tag_byte_size(tag: &Option<PropertyTag>) -> u32 {
+    match tag {
+        Some(PropertyTag {
+            property_guid: Some(_),
+            ..
+        }) => 16,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::IntProperty;
+    use binrw::BinRead;
+
+    #[paramacro::serialized_struct("Test")]
+    #[derive(Debug)]
+    struct StaticArrayStruct {
+        #[paramacro::serialized_field = "Values"]
+        values: [IntProperty; 2],
+    }
+
+    #[paramacro::serialized_struct("Test")]
+    #[derive(Debug)]
+    struct ColorStruct {
+        #[paramacro::serialized_field = "Color"]
+        color: LinearColorStruct,
+    }
+
+    #[test]
+    fn roundtrip_static_array() {
+        let data = [
+            0x07, 0x00, 0x00, 0x00, 0x56, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x00, 0x0c, 0x00, 0x00,
+            0x00, 0x49, 0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x04,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x07, 0x00,
+            0x00, 0x00, 0x56, 0x61, 0x6c, 0x75, 0x65, 0x73, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x49,
+            0x6e, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x04, 0x00, 0x00,
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00,
+            0x4e, 0x6f, 0x6e, 0x65, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let mut decoded = StaticArrayStruct::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.values[0].value, 1);
+        assert_eq!(decoded.values[1].value, 2);
+        assert_eq!(decoded.values[1].tag.array_index, 1);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+
+        // Editing an element only changes that element
+        decoded.values[1].value = 3;
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        let mut cursor = Cursor::new(&buffer);
+        let decoded = StaticArrayStruct::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.values[0].value, 1);
+        assert_eq!(decoded.values[1].value, 3);
+
+        // The second element can't be read as the first one
+        let mut cursor = Cursor::new(&data[40..]);
+        let err = StaticArrayStruct::read_le(&mut cursor).unwrap_err();
+        let err = err.custom_err::<Error>().unwrap();
+        assert_eq!(err.path(), Some("Values[0]"));
+        assert!(matches!(
+            err.root(),
+            Error::ArrayIndexMismatch {
+                expected: 0,
+                found: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn roundtrip_struct_guid() {
+        let data = [
+            0x06, 0x00, 0x00, 0x00, 0x43, 0x6f, 0x6c, 0x6f, 0x72, 0x00, 0x0f, 0x00, 0x00, 0x00,
+            0x53, 0x74, 0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6f, 0x70, 0x65, 0x72, 0x74, 0x79,
+            0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x4c,
+            0x69, 0x6e, 0x65, 0x61, 0x72, 0x43, 0x6f, 0x6c, 0x6f, 0x72, 0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x00,
+            0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x00, 0x3f, 0x00, 0x00, 0x80, 0x3e, 0x00, 0x00,
+            0x80, 0x3f, 0x05, 0x00, 0x00, 0x00, 0x4e, 0x6f, 0x6e, 0x65, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = ColorStruct::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.color.g, 0.5);
+        assert_eq!(
+            decoded.color_tag.as_ref().unwrap().struct_guid.unwrap().a,
+            0x04030201
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+
+        let mut cursor = Cursor::new(data);
+        let err = crate::read_strict::<ColorStruct, _>(&mut cursor).unwrap_err();
+        let err = err.custom_err::<Error>().unwrap();
+        assert_eq!(err.path(), Some("Color"));
+        assert_eq!(err.root(), &Error::NonZeroPadding { pos: 0x35 });
+    }
+}
@@ -0,0 +1,165 @@
+use binrw::{BinRead, BinResult, BinWrite, Endian, binrw};
+use std::fmt;
+use std::io::{Read, Seek, Write};
+
+use crate::common::{
+    read_string_with_length, size_of_string_with_length, write_string_with_length,
+};
+
+/// A path to an object, like `/Game/Dialogue/DA_Foo.DA_Foo` or `/Game/Maps/Map.Map:PersistentLevel.Actor`.
+///
+/// This is stored as a single string, which is split up into its parts when read and put back together exactly when written.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectPath {
+    /// Path of the package, like `/Game/Dialogue/DA_Foo`.
+    pub package_name: String,
+    /// Name of the asset in the package, which comes after the `.`.
+    pub asset_name: Option<String>,
+    /// Path of an object inside the asset, which comes after the `:`.
+    pub sub_path: Option<String>,
+}
+
+impl ObjectPath {
+    /// Splits up `path`, which doesn't have to be valid.
+    pub fn parse(path: &str) -> Self {
+        let (asset_path, sub_path) = match path.split_once(':') {
+            Some((asset_path, sub_path)) => (asset_path, Some(sub_path.to_string())),
+            None => (path, None),
+        };
+        let (package_name, asset_name) = match asset_path.split_once('.') {
+            Some((package_name, asset_name)) => (package_name, Some(asset_name.to_string())),
+            None => (asset_path, None),
+        };
+        Self {
+            package_name: package_name.to_string(),
+            asset_name,
+            sub_path,
+        }
+    }
+
+    pub(crate) fn size_in_bytes(&self) -> u32 {
+        size_of_string_with_length(&self.to_string())
+    }
+}
+
+impl fmt::Display for ObjectPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.package_name)?;
+        if let Some(asset_name) = &self.asset_name {
+            write!(f, ".{asset_name}")?;
+        }
+        if let Some(sub_path) = &self.sub_path {
+            write!(f, ":{sub_path}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BinRead for ObjectPath {
+    type Args<'a> = ();
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        _: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        Ok(Self::parse(&read_string_with_length(reader, endian, ())?))
+    }
+}
+
+impl BinWrite for ObjectPath {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        _: Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_string_with_length(&self.to_string(), writer, endian, ())
+    }
+}
+
+/// Written as the whole path, the same as it's stored.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ObjectPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ObjectPath {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::parse(&String::deserialize(deserializer)?))
+    }
+}
+
+/// A reference to an asset that may not be loaded.
+///
+/// See [the Unreal Engine documentation](https://dev.epicgames.com/documentation/en-us/unreal-engine/API/Runtime/CoreUObject/UObject/FSoftObjectPath?application_version=4.27).
+#[binrw]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftObjectPath {
+    /// Path of the asset, this doesn't usually have a sub path of its own.
+    pub asset_path: ObjectPath,
+
+    /// Path of an object inside the asset, or empty if it's the asset itself.
+    #[br(parse_with = read_string_with_length)]
+    #[bw(write_with = write_string_with_length)]
+    pub sub_path: String,
+}
+
+impl SoftObjectPath {
+    pub(crate) fn size_in_bytes(&self) -> u32 {
+        self.asset_path.size_in_bytes() + size_of_string_with_length(&self.sub_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn parse_paths() {
+        let path = ObjectPath::parse("/Game/Dialogue/DA_Foo.DA_Foo");
+        assert_eq!(path.package_name, "/Game/Dialogue/DA_Foo");
+        assert_eq!(path.asset_name.as_deref(), Some("DA_Foo"));
+        assert_eq!(path.sub_path, None);
+        assert_eq!(path.to_string(), "/Game/Dialogue/DA_Foo.DA_Foo");
+
+        let path = ObjectPath::parse("/Game/Maps/Map.Map:PersistentLevel.Actor");
+        assert_eq!(path.package_name, "/Game/Maps/Map");
+        assert_eq!(path.asset_name.as_deref(), Some("Map"));
+        assert_eq!(path.sub_path.as_deref(), Some("PersistentLevel.Actor"));
+        assert_eq!(path.to_string(), "/Game/Maps/Map.Map:PersistentLevel.Actor");
+
+        for path in ["", "None", "/Game/Foo.", "/Game/Foo:"] {
+            assert_eq!(ObjectPath::parse(path).to_string(), path);
+        }
+    }
+
+    #[test]
+    fn roundtrip_soft_object_path() {
+        let data = [
+            0x1d, 0x00, 0x00, 0x00, 0x2f, 0x47, 0x61, 0x6d, 0x65, 0x2f, 0x44, 0x69, 0x61, 0x6c,
+            0x6f, 0x67, 0x75, 0x65, 0x2f, 0x44, 0x41, 0x5f, 0x46, 0x6f, 0x6f, 0x2e, 0x44, 0x41,
+            0x5f, 0x46, 0x6f, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut cursor = Cursor::new(data);
+        let decoded = SoftObjectPath::read_le(&mut cursor).unwrap();
+        assert_eq!(decoded.asset_path.package_name, "/Game/Dialogue/DA_Foo");
+        assert_eq!(decoded.sub_path, "");
+
+        let mut buffer: Vec<u8> = Vec::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            decoded.write_le(&mut cursor).unwrap();
+        }
+
+        assert_eq!(data, &buffer[..]);
+        assert_eq!(decoded.size_in_bytes(), buffer.len() as u32);
+    }
+}
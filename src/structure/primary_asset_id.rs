@@ -4,6 +4,7 @@ use super::primary_asset_type::PrimaryAssetTypeStruct;
 
 #[paramacro::serialized_struct("PrimaryAssetId")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrimaryAssetIdStruct {
     #[paramacro::serialized_field = "PrimaryAssetType"]
     pub primary_asset_type: PrimaryAssetTypeStruct,
@@ -40,6 +41,6 @@ mod tests {
             0x65, 0x00,
         ];
         let mut cursor = Cursor::new(data);
-        let decoded = PrimaryAssetIdStruct::read_le(&mut cursor).unwrap();
+        PrimaryAssetIdStruct::read_le(&mut cursor).unwrap();
     }
 }
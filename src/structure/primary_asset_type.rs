@@ -2,6 +2,7 @@ use crate::property::NameProperty;
 
 #[paramacro::serialized_struct("PrimaryAssetType")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrimaryAssetTypeStruct {
     #[paramacro::serialized_field = "Name"]
     pub name: NameProperty,
@@ -28,6 +29,6 @@ mod tests {
             0x74, 0x69, 0x6f, 0x6e, 0x00, 0x05, 0x00, 0x00, 0x00, 0x4e, 0x6f, 0x6e, 0x65, 0x00,
         ];
         let mut cursor = Cursor::new(data);
-        let decoded = PrimaryAssetTypeStruct::read_le(&mut cursor).unwrap();
+        PrimaryAssetTypeStruct::read_le(&mut cursor).unwrap();
     }
 }
@@ -4,6 +4,7 @@ use crate::property::PropertyBase;
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QuatStruct {
     // TODO: check if w is actually in front or in the back, this is a guess
     pub w: f32,
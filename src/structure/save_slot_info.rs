@@ -4,6 +4,7 @@ use super::datetime::DateTimeStruct;
 
 #[paramacro::serialized_struct("SaveSlotInfo")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SaveSlotInfoStruct {
     #[paramacro::serialized_field = "Name"]
     pub name: StrProperty,
@@ -2,6 +2,7 @@ use super::{quat::QuatStruct, vector::VectorStruct};
 
 #[paramacro::serialized_struct("Transform")]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransformStruct {
     #[paramacro::serialized_field = "Rotation"]
     rotation: QuatStruct,
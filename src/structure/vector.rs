@@ -4,6 +4,7 @@ use crate::property::PropertyBase;
 
 #[binrw]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VectorStruct {
     pub x: f32,
     pub y: f32,
@@ -20,6 +21,6 @@ impl PropertyBase for VectorStruct {
     }
 
     fn size_in_bytes(&self) -> u32 {
-        16
+        12
     }
 }
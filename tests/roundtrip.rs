@@ -1,9 +1,15 @@
 use binrw::{BinRead, BinWrite};
-use ireko::TaggedSerialization;
-use ireko::save_object::generic::GenericTaggedObject;
+use ireko::save_object::generic::{GenericTaggedObject, Property};
+use ireko::save_object::{LocalProfileObject, PersistentObject, SlotObject};
+use ireko::structure::Guid;
+use ireko::{
+    CompressedSaveFile, CompressionOptions, Error, TaggedSerialization, compress_bytes,
+    decompress_to_bytes, read_strict,
+};
 use std::fs::read;
 use std::io::Cursor;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[test]
 fn roundtrip_localprofile() {
@@ -60,3 +66,484 @@ fn roundtrip_slot() {
     // Ensure our written version is the same as retail
     assert_eq!(new_data.as_slice(), &data[..]);
 }
+
+#[test]
+fn roundtrip_compressed() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("LocalProfile.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let local_profile = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+    let save_file = CompressedSaveFile {
+        options: CompressionOptions::default(),
+        value: local_profile,
+    };
+
+    let mut compressed_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut compressed_data);
+        save_file.write_le(&mut new_cursor).unwrap();
+    }
+
+    // Ensure it's actually written as a compressed block
+    assert_eq!(compressed_data[..4], [0xc1, 0x83, 0x2a, 0x9e]);
+
+    let mut cursor = Cursor::new(&compressed_data);
+    let save_file = CompressedSaveFile::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        save_file.value.write_le(&mut new_cursor).unwrap();
+    }
+
+    // Ensure the decompressed data is the same as retail
+    assert_eq!(new_data.as_slice(), &data[..]);
+}
+
+// The retail saves aren't in the repository, so this only runs when pointed at one, e.g.
+// `IREKO_RETAIL_SAV=LocalProfile.sav cargo test -- --ignored recompress_retail_save`
+#[test]
+#[ignore = "needs a retail .sav in IREKO_RETAIL_SAV"]
+fn recompress_retail_save() {
+    let data = read(std::env::var("IREKO_RETAIL_SAV").unwrap()).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let save_file = CompressedSaveFile::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        save_file.write_le(&mut new_cursor).unwrap();
+    }
+
+    // Ensure our compressed version is the same as retail
+    assert_eq!(new_data.len(), data.len());
+    assert_eq!(new_data, data);
+}
+
+#[test]
+fn read_corrupt_compressed() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("LocalProfile.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let local_profile = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+    let save_file = CompressedSaveFile {
+        options: CompressionOptions::default(),
+        value: local_profile,
+    };
+
+    let mut compressed_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut compressed_data);
+        save_file.write_le(&mut new_cursor).unwrap();
+    }
+
+    // Cut off the end of the only block
+    compressed_data.truncate(compressed_data.len() - 8);
+
+    let mut cursor = Cursor::new(&compressed_data);
+    let err = CompressedSaveFile::<GenericTaggedObject>::read_le(&mut cursor).unwrap_err();
+    let Some(Error::CorruptBlock {
+        block,
+        offset,
+        expected_size,
+        ..
+    }) = err.custom_err::<Error>()
+    else {
+        panic!("Expected a corrupt block, got {err}")
+    };
+    assert_eq!(*block, 0);
+    assert_eq!(*offset, 48);
+    assert_eq!(*expected_size, data.len() as u64);
+}
+
+#[test]
+fn roundtrip_raw_bytes() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("Slot.bin");
+
+    let data = read(d).unwrap();
+
+    let compressed_data = compress_bytes(&data, &CompressionOptions::default()).unwrap();
+    let decompressed_data = decompress_to_bytes(&mut Cursor::new(&compressed_data)).unwrap();
+    assert_eq!(decompressed_data, data);
+
+    // It should also be parsable like the game's saves
+    let mut cursor = Cursor::new(&compressed_data);
+    let save_file = CompressedSaveFile::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+    save_file.value.objs.entry("SlotInfo").unwrap();
+}
+
+#[test]
+fn read_error_path() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("Slot.bin");
+
+    let mut data = read(d).unwrap();
+
+    // Rename SlotInfo's Level field, so it no longer matches the struct
+    let pos = data.windows(5).position(|x| x == b"Level").unwrap();
+    data[pos + 1] = b'a';
+
+    let mut cursor = Cursor::new(&data);
+    let err = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap_err();
+    let err = err.custom_err::<Error>().unwrap();
+    assert_eq!(err.path(), Some("SlotInfo.Level"));
+    assert_eq!(err.pos(), pos as u64 - 4);
+    let Error::NameMismatch {
+        expected, found, ..
+    } = err.root()
+    else {
+        panic!("Expected a name mismatch, got {err}")
+    };
+    assert_eq!(expected, "Level");
+    assert_eq!(found, "Lavel");
+}
+
+#[test]
+fn read_strict_slot() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("Slot.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    // Retail saves don't have anything in the bytes we don't understand
+    read_strict::<TaggedSerialization<GenericTaggedObject>, _>(&mut cursor).unwrap();
+}
+
+#[test]
+fn edit_typed_slot() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("Slot.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let mut slot = TaggedSerialization::<SlotObject>::read_le(&mut cursor).unwrap();
+    assert_eq!(slot.objs.district_tag(), "District_M01A01_001");
+    assert!(slot.objs.is_demo());
+
+    // Writing it back without changes should give the same data
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        slot.write_le(&mut new_cursor).unwrap();
+    }
+    assert_eq!(new_data.as_slice(), &data[..]);
+
+    slot.objs.set_slot_name("Longer than before");
+    slot.objs.set_play_time(Duration::from_secs(90));
+    slot.objs.set_cycle_count(3);
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        slot.write_le(&mut new_cursor).unwrap();
+    }
+
+    // The sizes in front of the object have to grow with it
+    assert!(new_data.len() > data.len());
+    let size_in_bytes = u32::from_le_bytes(new_data[0..4].try_into().unwrap());
+    let objs_size_in_bytes = u32::from_le_bytes(new_data[4..8].try_into().unwrap());
+    assert_eq!(size_in_bytes as usize, new_data.len() - 4);
+    assert_eq!(objs_size_in_bytes, size_in_bytes - 4);
+
+    let mut cursor = Cursor::new(&new_data);
+    let slot = TaggedSerialization::<SlotObject>::read_le(&mut cursor).unwrap();
+    assert_eq!(slot.objs.slot_name(), "Longer than before");
+    assert_eq!(slot.objs.play_time(), Duration::from_secs(90));
+    assert_eq!(slot.objs.cycle_count(), 3);
+    assert_eq!(slot.objs.district_tag(), "District_M01A01_001");
+}
+
+#[test]
+fn roundtrip_typed_localprofile() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("LocalProfile.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let local_profile = TaggedSerialization::<LocalProfileObject>::read_le(&mut cursor).unwrap();
+    assert!(local_profile.objs.is_demo());
+    assert_eq!(local_profile.objs.version(), 0);
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        local_profile.write_le(&mut new_cursor).unwrap();
+    }
+
+    // Ensure our written version is the same as retail
+    assert_eq!(new_data.as_slice(), &data[..]);
+}
+
+// There's no retail Persistent.sav to test against yet, so SyntheticPersistent.bin is put together by hand following the
+// layout of the other saves. Only the build data in CurrentBuildData and SavedBuildData comes from a real save, so these
+// tests only show that we read and write our own idea of the layout consistently, see recompress_retail_persistent.
+#[test]
+fn roundtrip_synthetic_persistent() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("SyntheticPersistent.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let persistent = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+    persistent.objs.entry("CurrentBuildData").unwrap();
+    persistent.objs.entry("bUseSaveSlot").unwrap();
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        persistent.write_le(&mut new_cursor).unwrap();
+    }
+
+    assert_eq!(new_data.as_slice(), &data[..]);
+}
+
+#[test]
+fn roundtrip_typed_synthetic_persistent() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("SyntheticPersistent.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let mut persistent = TaggedSerialization::<PersistentObject>::read_le(&mut cursor).unwrap();
+    assert_eq!(persistent.objs.money(), 125000);
+    assert_eq!(persistent.objs.current_item_slot(), 1);
+    assert_eq!(persistent.objs.normal_item_inventory().len(), 2);
+    assert_eq!(persistent.objs.item_slots().count(), 2);
+    assert_eq!(persistent.objs.acquired_item_box_ids().count(), 2);
+    assert_eq!(persistent.objs.current_build_data().name(), "");
+    assert_eq!(persistent.objs.saved_build_data().count(), 1);
+    assert_eq!(
+        persistent.objs.reached_districts().next(),
+        Some("District_M01A01_001")
+    );
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        persistent.write_le(&mut new_cursor).unwrap();
+    }
+
+    // Ensure our written version is the same as the fixture
+    assert_eq!(new_data.as_slice(), &data[..]);
+
+    persistent.objs.set_money(999999);
+    assert!(persistent.objs.insert_opened_strong_box_id(Guid {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+    }));
+    persistent.objs.set_tuning_points(7);
+    persistent
+        .objs
+        .saved_build_data_mut()
+        .next()
+        .unwrap()
+        .set_name("Build 2");
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        persistent.write_le(&mut new_cursor).unwrap();
+    }
+
+    let mut cursor = Cursor::new(&new_data);
+    let persistent = TaggedSerialization::<PersistentObject>::read_le(&mut cursor).unwrap();
+    assert_eq!(persistent.objs.money(), 999999);
+    assert_eq!(persistent.objs.opened_strong_box_ids().count(), 2);
+    assert_eq!(persistent.objs.tuning_points(), 7);
+    assert_eq!(
+        persistent.objs.saved_build_data().next().unwrap().name(),
+        "Build 2"
+    );
+}
+
+#[test]
+fn roundtrip_compressed_synthetic_persistent() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("SyntheticPersistent.bin");
+
+    let data = read(d).unwrap();
+    // Small blocks, so the data is split up like a retail save of this size would be
+    let options = CompressionOptions {
+        block_size: 0x1000,
+        chunk_size: 0x800,
+        ..Default::default()
+    };
+    let compressed_data = compress_bytes(&data, &options).unwrap();
+
+    let mut cursor = Cursor::new(&compressed_data);
+    let save_file = CompressedSaveFile::<PersistentObject>::read_le(&mut cursor).unwrap();
+    assert_eq!(save_file.options, options);
+    assert_eq!(save_file.value.objs.money(), 125000);
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        save_file.write_le(&mut new_cursor).unwrap();
+    }
+
+    assert_eq!(new_data, compressed_data);
+}
+
+// Like recompress_retail_save, this only runs when pointed at a retail Persistent.sav, e.g.
+// `IREKO_RETAIL_PERSISTENT_SAV=Persistent.sav cargo test -- --ignored recompress_retail_persistent`
+#[test]
+#[ignore = "needs a retail Persistent.sav in IREKO_RETAIL_PERSISTENT_SAV"]
+fn recompress_retail_persistent() {
+    let data = read(std::env::var("IREKO_RETAIL_PERSISTENT_SAV").unwrap()).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let save_file = CompressedSaveFile::<PersistentObject>::read_le(&mut cursor).unwrap();
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        save_file.write_le(&mut new_cursor).unwrap();
+    }
+
+    // The compressed bytes also depend on the zlib implementation, so compare what's in them
+    assert_eq!(
+        decompress_to_bytes(&mut Cursor::new(&new_data)).unwrap(),
+        decompress_to_bytes(&mut Cursor::new(&data)).unwrap()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn roundtrip_json() {
+    for (name, size) in [("Slot.bin", 900), ("SyntheticPersistent.bin", 21231)] {
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("tests/resources");
+        d.push(name);
+
+        let data = read(d).unwrap();
+        let mut cursor = Cursor::new(&data);
+
+        let save = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+        let json = serde_json::to_string(&save).unwrap();
+        let save: TaggedSerialization<GenericTaggedObject> = serde_json::from_str(&json).unwrap();
+        assert_eq!(save.objs.size_in_bytes, size);
+
+        let mut new_data: Vec<u8> = Vec::new();
+        {
+            let mut new_cursor = Cursor::new(&mut new_data);
+            save.write_le(&mut new_cursor).unwrap();
+        }
+
+        // Nothing should be lost going through JSON
+        assert_eq!(new_data.as_slice(), &data[..]);
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn roundtrip_json_cli() {
+    use std::process::Command;
+
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("SyntheticPersistent.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let persistent = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+    let save_file = CompressedSaveFile {
+        options: CompressionOptions::default(),
+        value: persistent,
+    };
+
+    let mut compressed_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut compressed_data);
+        save_file.write_le(&mut new_cursor).unwrap();
+    }
+
+    // Removed when dropped, even if an assert below fails
+    let dir = tempfile::tempdir().unwrap();
+    let sav_path = dir.path().join("Persistent.sav");
+    let json_path = dir.path().join("Persistent.json");
+    let new_sav_path = dir.path().join("New.sav");
+    std::fs::write(&sav_path, &compressed_data).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ireko"))
+        .arg("to-json")
+        .arg(&sav_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    std::fs::write(&json_path, &output.stdout).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_ireko"))
+        .arg("from-json")
+        .arg(&json_path)
+        .arg("-o")
+        .arg(&new_sav_path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let new_data = read(&new_sav_path).unwrap();
+
+    // Ensure nothing was changed on the way through JSON
+    assert_eq!(new_data, compressed_data);
+}
+
+#[test]
+fn write_edited_generic_slot() {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/resources");
+    d.push("Slot.bin");
+
+    let data = read(d).unwrap();
+    let mut cursor = Cursor::new(&data);
+
+    let mut slot = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+    let entry = slot
+        .objs
+        .entries
+        .iter_mut()
+        .find(|e| e.name == "RegisteredName")
+        .unwrap();
+    let Some(Property::String(name)) = &mut entry.r#type else {
+        panic!("StrProperty!")
+    };
+    name.value = "Longer than before".to_string();
+
+    let mut new_data: Vec<u8> = Vec::new();
+    {
+        let mut new_cursor = Cursor::new(&mut new_data);
+        slot.write_le(&mut new_cursor).unwrap();
+    }
+
+    // The sizes in front of the object have to grow with it
+    let mut cursor = Cursor::new(&new_data);
+    let slot = TaggedSerialization::<GenericTaggedObject>::read_le(&mut cursor).unwrap();
+    assert_eq!(slot.size_in_bytes as usize, new_data.len() - 4);
+    assert_eq!(slot.objs.size_in_bytes, slot.size_in_bytes - 4);
+    assert_eq!(slot.objs.entry("SlotInfo").unwrap().name, "SlotInfo");
+}